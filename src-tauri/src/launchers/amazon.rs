@@ -0,0 +1,233 @@
+// Amazon Games (Prime Gaming) library.
+//
+// Installs are rows of the DbSet table in
+// %LOCALAPPDATA%\Amazon Games\Data\Games\Sql\GameInstallInfo.sqlite, and each
+// install folder has a fuel.json naming the main executable. Games are
+// launched through the app with `amazon-games://play/<id>` so entitlement
+// checks keep working.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rusqlite::{Connection, OpenFlags};
+use serde::Deserialize;
+
+use super::{host_path, local_app_data, uri_launch_available, Launcher, ScanResult};
+use crate::GameInfo;
+
+const LAUNCHER_ID: &str = "amazon";
+
+#[derive(Debug, Clone, Default)]
+pub struct AmazonInstall {
+    pub id: String,
+    pub title: String,
+    pub install_directory: String,
+    pub installed: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct FuelManifest {
+    pub main: FuelMain,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct FuelMain {
+    pub command: String,
+    pub args: Vec<String>,
+    pub working_subdir_override: Option<String>,
+}
+
+pub fn app_dir() -> PathBuf {
+    local_app_data().join("Amazon Games")
+}
+
+pub fn database_path() -> PathBuf {
+    app_dir()
+        .join("Data")
+        .join("Games")
+        .join("Sql")
+        .join("GameInstallInfo.sqlite")
+}
+
+pub fn read_installs(db_path: &Path) -> Result<Vec<AmazonInstall>, String> {
+    let conn = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX)
+        .map_err(|e| format!("Failed to open {}: {}", db_path.display(), e))?;
+
+    let mut stmt = conn
+        .prepare("SELECT Id, ProductTitle, InstallDirectory, Installed FROM DbSet ORDER BY ProductTitle")
+        .map_err(|e| format!("Failed to query Amazon installs: {}", e))?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(AmazonInstall {
+                id: row.get(0)?,
+                title: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                install_directory: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                installed: row.get::<_, Option<i64>>(3)?.unwrap_or(0) != 0,
+            })
+        })
+        .map_err(|e| format!("Failed to read Amazon installs: {}", e))?;
+
+    Ok(rows.flatten().collect())
+}
+
+pub fn read_fuel(install_dir: &Path) -> Result<FuelManifest, String> {
+    let path = install_dir.join("fuel.json");
+    let text = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(text.trim_start_matches('\u{feff}')).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+/// `Main.Args` as one command line, quoting arguments that contain spaces.
+pub fn join_args(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| {
+            if arg.is_empty() || arg.contains(char::is_whitespace) {
+                format!("\"{}\"", arg)
+            } else {
+                arg.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn launch_uri(id: &str) -> String {
+    format!("amazon-games://play/{}", id)
+}
+
+pub fn scan() -> ScanResult {
+    scan_database(&database_path())
+}
+
+pub fn scan_database(db_path: &Path) -> ScanResult {
+    let mut result = ScanResult::default();
+
+    let installs = match read_installs(db_path) {
+        Ok(installs) => installs,
+        Err(e) => {
+            println!("{}", e);
+            return result;
+        }
+    };
+
+    for install in installs {
+        let name = if install.title.is_empty() { install.id.clone() } else { install.title.clone() };
+
+        if !install.installed {
+            result.skip(LAUNCHER_ID, &install.id, &name, "Not installed");
+            continue;
+        }
+        let install_dir = host_path(&install.install_directory);
+        if !install_dir.is_dir() {
+            result.skip(
+                LAUNCHER_ID,
+                &install.id,
+                &name,
+                format!("Install folder missing: {}", install.install_directory),
+            );
+            continue;
+        }
+
+        // fuel.json only provides the executable and its arguments; the URI
+        // works without it, except inside a Wine prefix.
+        let main = match read_fuel(&install_dir) {
+            Ok(fuel) => fuel.main,
+            Err(e) => {
+                println!("{}", e);
+                FuelMain::default()
+            }
+        };
+        if main.command.is_empty() && !uri_launch_available() {
+            result.skip(LAUNCHER_ID, &install.id, &name, "No Main.Command in fuel.json");
+            continue;
+        }
+        let working_dir = main
+            .working_subdir_override
+            .filter(|dir| !dir.is_empty())
+            .map(|dir| install_dir.join(dir).to_string_lossy().to_string());
+        let launch_options = Some(join_args(&main.args)).filter(|args| !args.is_empty());
+
+        result.games.push(GameInfo {
+            id: install.id.clone(),
+            name,
+            executable: main.command,
+            install_path: install.install_directory.clone(),
+            launcher_id: LAUNCHER_ID.to_string(),
+            launch_uri: Some(launch_uri(&install.id)),
+            launch_options,
+            working_dir,
+            ..Default::default()
+        });
+    }
+
+    result
+}
+
+pub struct Amazon;
+
+impl Launcher for Amazon {
+    fn id(&self) -> &str {
+        LAUNCHER_ID
+    }
+
+    fn name(&self) -> &str {
+        "Amazon Games"
+    }
+
+    fn icon(&self) -> &str {
+        "📦"
+    }
+
+    fn detect(&self) -> bool {
+        app_dir().join("App").join("Amazon Games.exe").exists() || database_path().exists()
+    }
+
+    fn install_path(&self) -> Option<String> {
+        Some(app_dir().to_string_lossy().to_string())
+    }
+
+    fn scan_games(&self) -> ScanResult {
+        scan()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn passes_fuel_arguments_as_launch_options() {
+        let root = TempDir::new("amazon");
+        let install_dir = root.join("Fixture Quest");
+        fs::create_dir_all(&install_dir).unwrap();
+        fs::write(
+            install_dir.join("fuel.json"),
+            r#"{"SchemaVersion":"2","Main":{"Command":"Bin\\Quest.exe","Args":["-skipintro","-profile","Prime Gaming"]}}"#,
+        )
+        .unwrap();
+
+        let db = root.join("GameInstallInfo.sqlite");
+        let conn = Connection::open(&db).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE DbSet (Id TEXT, ProductTitle TEXT, InstallDirectory TEXT, Installed INTEGER);",
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO DbSet VALUES ('amzn1.adg.product.fixture', 'Fixture Quest', ?1, 1)",
+            [install_dir.to_string_lossy().to_string()],
+        )
+        .unwrap();
+        drop(conn);
+
+        let result = scan_database(&db);
+        assert!(result.skipped.is_empty());
+        assert_eq!(result.games.len(), 1);
+        let game = &result.games[0];
+        assert_eq!(game.executable, "Bin\\Quest.exe");
+        assert_eq!(game.launch_options.as_deref(), Some("-skipintro -profile \"Prime Gaming\""));
+        assert_eq!(game.launch_uri.as_deref(), Some("amazon-games://play/amzn1.adg.product.fixture"));
+    }
+}
@@ -0,0 +1,297 @@
+// Battle.net installs, decoded from the agent's protobuf database at
+// %PROGRAMDATA%\Battle.net\Agent\product.db.
+//
+// Relevant schema (Blizzard's ProductDb, field numbers only):
+//   Database        { repeated ProductInstall product_install = 1; }
+//   ProductInstall  { uid = 1; product_code = 2; UserSettings settings = 3;
+//                     CachedProductState cached_product_state = 4; }
+//   UserSettings    { install_path = 1; }
+//   CachedProductState { BaseProductState base_product_state = 1; }
+//   BaseProductState   { installed = 1; playable = 2; }
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::protobuf::{self, FieldValue};
+use super::{host_path, path_exists, program_data, registry_key_exists, uri_launch_available, Launcher, ScanResult};
+use crate::GameInfo;
+
+const LAUNCHER_ID: &str = "battlenet";
+
+/// Products in the database that are not games.
+const NON_GAME_PRODUCTS: &[&str] = &["agent", "bna", "battle.net"];
+
+/// product code -> (display name, battlenet:// launch code, main executable).
+const KNOWN_PRODUCTS: &[(&str, &str, &str, &str)] = &[
+    ("wow", "World of Warcraft", "WoW", "_retail_\\Wow.exe"),
+    ("wow_classic", "World of Warcraft Classic", "WoWC", "_classic_\\WowClassic.exe"),
+    ("d3", "Diablo III", "D3", "Diablo III64.exe"),
+    ("fenris", "Diablo IV", "Fen", "Diablo IV.exe"),
+    ("osi", "Diablo II: Resurrected", "OSI", "D2R.exe"),
+    ("anbs", "Diablo Immortal", "ANBS", "DiabloImmortal.exe"),
+    ("pro", "Overwatch 2", "Pro", "_retail_\\Overwatch.exe"),
+    ("hero", "Heroes of the Storm", "Hero", ""),
+    ("s1", "StarCraft: Remastered", "S1", "x86_64\\StarCraft.exe"),
+    ("s2", "StarCraft II", "S2", ""),
+    ("hs_beta", "Hearthstone", "WTCG", "Hearthstone.exe"),
+    ("w3", "Warcraft III: Reforged", "W3", "_retail_\\x86_64\\Warcraft III.exe"),
+    ("rtro", "Blizzard Arcade Collection", "RTRO", ""),
+    ("gryphon", "Warcraft Rumble", "GRY", ""),
+    ("wlby", "Crash Bandicoot 4: It's About Time", "WLBY", ""),
+    ("viper", "Call of Duty: Black Ops 4", "VIPR", ""),
+    ("odin", "Call of Duty: Modern Warfare", "ODIN", ""),
+    ("lazarus", "Call of Duty: Modern Warfare 2 Campaign Remastered", "LAZR", ""),
+    ("zeus", "Call of Duty: Black Ops Cold War", "ZEUS", ""),
+    ("fore", "Call of Duty: Vanguard", "FORE", ""),
+    ("auks", "Call of Duty", "AUKS", ""),
+];
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProductInstall {
+    pub uid: String,
+    pub product_code: String,
+    pub install_path: String,
+    pub installed: bool,
+    pub playable: bool,
+}
+
+pub fn database_path() -> PathBuf {
+    program_data().join("Battle.net").join("Agent").join("product.db")
+}
+
+pub fn parse_product_db(data: &[u8]) -> Result<Vec<ProductInstall>, String> {
+    let mut installs = Vec::new();
+
+    for (number, value) in protobuf::parse_message(data)? {
+        let (1, FieldValue::Bytes(install)) = (number, value) else {
+            continue;
+        };
+
+        let mut product = ProductInstall::default();
+        for (field, value) in protobuf::parse_message(install)? {
+            match field {
+                1 => product.uid = value.as_string().unwrap_or_default(),
+                2 => product.product_code = value.as_string().unwrap_or_default(),
+                3 => {
+                    let settings = protobuf::parse_message(value.as_bytes().unwrap_or_default())?;
+                    if let Some((_, path)) = settings.iter().find(|(n, _)| *n == 1) {
+                        product.install_path = path.as_string().unwrap_or_default();
+                    }
+                }
+                4 => {
+                    let state = protobuf::parse_message(value.as_bytes().unwrap_or_default())?;
+                    if let Some((_, base)) = state.iter().find(|(n, _)| *n == 1) {
+                        for (flag, value) in protobuf::parse_message(base.as_bytes().unwrap_or_default())? {
+                            match flag {
+                                1 => product.installed = value.as_u64() == Some(1),
+                                2 => product.playable = value.as_u64() == Some(1),
+                                _ => {}
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        installs.push(product);
+    }
+
+    Ok(installs)
+}
+
+/// Display name, `battlenet://` code and executable for a product code.
+/// Unknown products fall back to their code, exactly as product.db stores it,
+/// so they can still be launched.
+pub fn product_details(product_code: &str) -> (String, String, String) {
+    match KNOWN_PRODUCTS
+        .iter()
+        .find(|(code, ..)| code.eq_ignore_ascii_case(product_code))
+    {
+        Some((_, name, launch_code, exe)) => (name.to_string(), launch_code.to_string(), exe.to_string()),
+        None => (product_code.to_uppercase(), product_code.to_string(), String::new()),
+    }
+}
+
+pub fn launch_uri(launch_code: &str) -> String {
+    format!("battlenet://{}", launch_code)
+}
+
+pub fn scan() -> ScanResult {
+    scan_database(&database_path())
+}
+
+pub fn scan_database(db_path: &Path) -> ScanResult {
+    let mut result = ScanResult::default();
+
+    let installs = match fs::read(db_path).map_err(|e| e.to_string()).and_then(|data| parse_product_db(&data)) {
+        Ok(installs) => installs,
+        Err(e) => {
+            println!("Failed to read {}: {}", db_path.display(), e);
+            return result;
+        }
+    };
+
+    for install in installs {
+        if NON_GAME_PRODUCTS.contains(&install.product_code.to_lowercase().as_str()) {
+            continue;
+        }
+
+        let (name, launch_code, executable) = product_details(&install.product_code);
+        let id = if install.uid.is_empty() { install.product_code.clone() } else { install.uid.clone() };
+
+        if install.install_path.is_empty() {
+            result.skip(LAUNCHER_ID, &id, &name, "No install path in product.db");
+            continue;
+        }
+        if !install.installed && !install.playable {
+            result.skip(LAUNCHER_ID, &id, &name, "Not installed");
+            continue;
+        }
+        // product.db stores forward slashes.
+        let install_path = install.install_path.replace('/', "\\");
+        if !host_path(&install_path).is_dir() {
+            result.skip(LAUNCHER_ID, &id, &name, format!("Install folder missing: {}", install_path));
+            continue;
+        }
+        if executable.is_empty() && !uri_launch_available() {
+            result.skip(LAUNCHER_ID, &id, &name, "No known executable for this product");
+            continue;
+        }
+
+        result.games.push(GameInfo {
+            id,
+            name,
+            executable,
+            install_path,
+            launcher_id: LAUNCHER_ID.to_string(),
+            launch_uri: Some(launch_uri(&launch_code)),
+            ..Default::default()
+        });
+    }
+
+    result
+}
+
+pub struct BattleNet;
+
+impl Launcher for BattleNet {
+    fn id(&self) -> &str {
+        LAUNCHER_ID
+    }
+
+    fn name(&self) -> &str {
+        "Battle.net"
+    }
+
+    fn icon(&self) -> &str {
+        "⚔️"
+    }
+
+    fn detect(&self) -> bool {
+        registry_key_exists("SOFTWARE\\WOW6432Node\\Blizzard Entertainment\\Battle.net")
+            || path_exists("C:\\Program Files (x86)\\Battle.net")
+            || path_exists("C:\\Program Files\\Battle.net")
+    }
+
+    fn install_path(&self) -> Option<String> {
+        Some("C:\\Program Files (x86)\\Battle.net".to_string())
+    }
+
+    fn scan_games(&self) -> ScanResult {
+        scan()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::launchers::protobuf::tests::Message;
+    use crate::launchers::wine::{self, WinePrefix};
+    use crate::test_support::TempDir;
+
+    fn install(uid: &str, code: &str, path: &str, installed: u64, playable: u64) -> Message {
+        Message::default()
+            .string(1, uid)
+            .string(2, code)
+            .message(3, Message::default().string(1, path).string(2, "enUS"))
+            .message(
+                4,
+                Message::default().message(1, Message::default().varint(1, installed).varint(2, playable).varint(3, 1)),
+            )
+    }
+
+    #[test]
+    fn decodes_product_installs() {
+        let data = Message::default()
+            .message(1, install("fenris", "fenris", "C:/Program Files (x86)/Diablo IV", 1, 1))
+            .message(1, install("s2_enus", "s2", "C:/Program Files (x86)/StarCraft II", 0, 1))
+            .message(1, Message::default().string(1, "agent").string(2, "agent"))
+            .string(2, "ignored")
+            .build();
+
+        let installs = parse_product_db(&data).unwrap();
+        assert_eq!(installs.len(), 3);
+        assert_eq!(
+            installs[0],
+            ProductInstall {
+                uid: "fenris".into(),
+                product_code: "fenris".into(),
+                install_path: "C:/Program Files (x86)/Diablo IV".into(),
+                installed: true,
+                playable: true,
+            }
+        );
+        assert_eq!(installs[1].product_code, "s2");
+        assert!(!installs[1].installed && installs[1].playable);
+        assert_eq!(installs[2], ProductInstall { uid: "agent".into(), product_code: "agent".into(), ..Default::default() });
+    }
+
+    #[test]
+    fn rejects_truncated_databases() {
+        let data = Message::default().message(1, install("fenris", "fenris", "C:/Games/Diablo IV", 1, 1)).build();
+        for len in [1, 2, data.len() / 2, data.len() - 1] {
+            assert!(parse_product_db(&data[..len]).is_err(), "accepted {} of {} bytes", len, data.len());
+        }
+
+        // A settings message cut short inside an otherwise valid install.
+        let settings = Message::default().string(1, "C:/Games/Diablo IV").build();
+        let product = Message::default().string(2, "fenris").bytes(3, &settings[..settings.len() - 3]).build();
+        assert!(parse_product_db(&Message::default().bytes(1, &product).build()).is_err());
+    }
+
+    #[test]
+    fn unknown_products_launch_by_their_stored_code() {
+        let (name, launch_code, executable) = product_details("Pinta");
+        assert_eq!((name.as_str(), launch_code.as_str(), executable.as_str()), ("PINTA", "Pinta", ""));
+        assert_eq!(launch_uri(&launch_code), "battlenet://Pinta");
+
+        let (_, launch_code, _) = product_details("FENRIS");
+        assert_eq!(launch_code, "Fen");
+    }
+
+    #[test]
+    fn products_without_an_executable_are_skipped_in_a_prefix() {
+        let root = TempDir::new("battlenet-prefix");
+        fs::write(root.join("system.reg"), "WINE REGISTRY Version 2\n").unwrap();
+        let games = root.join("drive_c").join("Games");
+        fs::create_dir_all(games.join("Diablo IV")).unwrap();
+        fs::create_dir_all(games.join("StarCraft II")).unwrap();
+        let db = root.join("product.db");
+        fs::write(
+            &db,
+            Message::default()
+                .message(1, install("fenris", "fenris", "C:/Games/Diablo IV", 1, 1))
+                .message(1, install("s2_enus", "s2", "C:/Games/StarCraft II", 1, 1))
+                .build(),
+        )
+        .unwrap();
+
+        // StarCraft II only launches through battlenet://, which a prefix has no handler for.
+        let result = wine::with_prefix(&WinePrefix::new(root.path()), || scan_database(&db)).unwrap();
+        assert_eq!(result.games.len(), 1);
+        assert_eq!(result.games[0].executable, "Diablo IV.exe");
+        assert_eq!(result.skipped.len(), 1);
+        assert_eq!(result.skipped[0].name, "StarCraft II");
+        assert_eq!(result.skipped[0].reason, "No known executable for this product");
+    }
+}
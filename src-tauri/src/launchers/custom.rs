@@ -0,0 +1,500 @@
+// User-defined launchers from `launchers.json` in the app config folder.
+//
+// Each entry says how to tell the launcher is installed, where its games are
+// and how to start them:
+//
+//     {
+//       "launchers": [
+//         {
+//           "id": "studio",
+//           "name": "Studio Tools",
+//           "icon": "🧰",
+//           "detect": {
+//             "registry_keys": ["SOFTWARE\\Studio\\Tools"],
+//             "paths": ["C:\\Studio\\Tools"]
+//           },
+//           "install_path": "C:\\Studio\\Tools",
+//           "game_folders": ["C:\\Studio\\Games\\*", "~/studio/**/game-*"],
+//           "executable": {
+//             "include": ["*.exe"],
+//             "exclude": ["unins*.exe"],
+//             "max_depth": 2
+//           },
+//           "launch": {
+//             "executable": "{exe}",
+//             "arguments": "--profile {id}",
+//             "working_dir": "{folder}"
+//           }
+//         }
+//       ]
+//     }
+//
+// Every field but `id` and `name` is optional. A launcher without detection
+// rules counts as detected when any game folder matches. Paths may start
+// with `~/` or contain `%VARIABLE%`s. In game folder patterns `*` and `?`
+// match within one folder name and `**` matches any number of folders.
+// Launch templates can use `{exe}`, `{folder}`, `{name}` and `{id}`; a
+// `uri` template makes the game start through that URI instead.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use serde::Deserialize;
+
+use super::{
+    app_data, home_dir, host_path, local_app_data, path_exists, program_data, registry_key_exists, user_profile, Launcher,
+    ScanResult,
+};
+use crate::GameInfo;
+
+pub const CONFIG_FILE: &str = "launchers.json";
+
+/// Files that are almost never the game itself.
+const DEFAULT_EXCLUDE: &[&str] = &[
+    "unins*",
+    "*setup*",
+    "*install*",
+    "*crash*",
+    "*redist*",
+    "vc_redist*",
+    "dxsetup*",
+    "*update*",
+];
+
+static CONFIG_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct CustomLaunchersFile {
+    pub launchers: Vec<CustomLauncher>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct CustomLauncher {
+    pub id: String,
+    pub name: String,
+    pub icon: Option<String>,
+    pub detect: DetectRules,
+    pub install_path: Option<String>,
+    pub game_folders: Vec<String>,
+    pub executable: ExecutableRules,
+    pub launch: LaunchTemplate,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct DetectRules {
+    /// Keys under HKEY_LOCAL_MACHINE.
+    pub registry_keys: Vec<String>,
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ExecutableRules {
+    /// File name patterns an executable must match.
+    pub include: Vec<String>,
+    /// File name patterns to ignore, on top of installers and crash handlers.
+    pub exclude: Vec<String>,
+    /// How many folders below the game folder to look.
+    pub max_depth: usize,
+}
+
+impl Default for ExecutableRules {
+    fn default() -> Self {
+        Self {
+            include: vec!["*.exe".to_string()],
+            exclude: Vec::new(),
+            max_depth: 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LaunchTemplate {
+    pub executable: Option<String>,
+    pub arguments: Option<String>,
+    pub working_dir: Option<String>,
+    pub uri: Option<String>,
+}
+
+/// Sets the folder `launchers.json` is read from. Called once the app knows
+/// its config folder.
+pub fn set_config_dir(dir: PathBuf) {
+    *CONFIG_DIR.write().unwrap() = Some(dir);
+}
+
+/// `launchers.json` in the app config folder, or in `config/` of the fixture
+/// root when one is set.
+pub fn config_path() -> Option<PathBuf> {
+    if let Some(root) = crate::sandbox::root() {
+        return Some(root.join("config").join(CONFIG_FILE));
+    }
+    CONFIG_DIR.read().unwrap().as_ref().map(|dir| dir.join(CONFIG_FILE))
+}
+
+pub fn parse_config(json: &str) -> Result<Vec<CustomLauncher>, String> {
+    let file: CustomLaunchersFile = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    for launcher in &file.launchers {
+        if launcher.id.is_empty() || launcher.name.is_empty() {
+            return Err("Every launcher needs an id and a name".to_string());
+        }
+    }
+    Ok(file.launchers)
+}
+
+/// The launchers declared in the config file, or none if there is no file.
+pub fn load() -> Vec<CustomLauncher> {
+    let Some(path) = config_path() else {
+        return Vec::new();
+    };
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
+        Err(e) => {
+            println!("Failed to read {}: {}", path.display(), e);
+            return Vec::new();
+        }
+    };
+    parse_config(&text)
+        .map_err(|e| println!("Failed to parse {}: {}", path.display(), e))
+        .unwrap_or_default()
+}
+
+impl CustomLauncher {
+    /// Every folder matched by the game folder patterns.
+    pub fn game_folders(&self) -> Vec<PathBuf> {
+        let mut folders: Vec<PathBuf> = self
+            .game_folders
+            .iter()
+            .flat_map(|pattern| expand_glob(&resolve(pattern)))
+            .filter(|path| path.is_dir())
+            .collect();
+        folders.sort();
+        folders.dedup();
+        folders
+    }
+
+    /// The executable to start for a game folder: the shallowest match,
+    /// then the largest file.
+    pub fn pick_executable(&self, folder: &Path) -> Option<PathBuf> {
+        let rules = &self.executable;
+        let mut candidates: Vec<(usize, u64, PathBuf)> = walkdir::WalkDir::new(folder)
+            .max_depth(rules.max_depth.max(1))
+            .into_iter()
+            .flatten()
+            .filter(|entry| entry.file_type().is_file())
+            .filter_map(|entry| {
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                let included = rules.include.iter().any(|p| glob_match(p, &file_name));
+                let excluded = DEFAULT_EXCLUDE
+                    .iter()
+                    .copied()
+                    .chain(rules.exclude.iter().map(String::as_str))
+                    .any(|p| glob_match(p, &file_name));
+                if !included || excluded {
+                    return None;
+                }
+                let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                Some((entry.depth(), size, entry.into_path()))
+            })
+            .collect();
+        candidates.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)).then(a.2.cmp(&b.2)));
+        candidates.into_iter().next().map(|(_, _, path)| path)
+    }
+
+    fn game_for_folder(&self, folder: &Path, result: &mut ScanResult) -> Option<GameInfo> {
+        let name = folder.file_name()?.to_string_lossy().to_string();
+        let id = name.to_lowercase().replace(' ', "-");
+
+        let exe = self.pick_executable(folder);
+        let needs_exe = self.launch.uri.is_none() || self.launch.executable.as_deref().is_some_and(|t| t.contains("{exe}"));
+        if exe.is_none() && needs_exe {
+            result.skip(&self.id, &id, &name, "No executable matched");
+            return None;
+        }
+
+        let exe_path = exe.map(|p| p.to_string_lossy().to_string()).unwrap_or_default();
+        let folder_path = folder.to_string_lossy().to_string();
+        let fill = |template: &str| {
+            template
+                .replace("{exe}", &exe_path)
+                .replace("{folder}", &folder_path)
+                .replace("{name}", &name)
+                .replace("{id}", &id)
+        };
+
+        Some(GameInfo {
+            id: id.clone(),
+            name: name.clone(),
+            executable: fill(self.launch.executable.as_deref().unwrap_or("{exe}")),
+            install_path: folder_path.clone(),
+            launcher_id: self.id.clone(),
+            launch_uri: self.launch.uri.as_deref().map(fill),
+            launch_options: self.launch.arguments.as_deref().map(fill).filter(|a| !a.is_empty()),
+            working_dir: self.launch.working_dir.as_deref().map(fill),
+            ..Default::default()
+        })
+    }
+}
+
+impl Launcher for CustomLauncher {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn icon(&self) -> &str {
+        self.icon.as_deref().unwrap_or("🧩")
+    }
+
+    fn detect(&self) -> bool {
+        let rules = &self.detect;
+        if rules.registry_keys.is_empty() && rules.paths.is_empty() {
+            return !self.game_folders().is_empty();
+        }
+        rules.registry_keys.iter().any(|key| registry_key_exists(key))
+            || rules.paths.iter().any(|path| path_exists(&resolve(path)))
+    }
+
+    fn install_path(&self) -> Option<String> {
+        self.install_path.as_deref().map(resolve)
+    }
+
+    /// Only launchers described with Windows paths are looked for in Wine
+    /// prefixes; anything else would be found once per prefix.
+    fn runs_in_wine(&self) -> bool {
+        let is_windows_path = |path: &String| path.as_bytes().get(1) == Some(&b':');
+        self.detect.paths.iter().chain(&self.game_folders).any(is_windows_path)
+            || !self.detect.registry_keys.is_empty()
+    }
+
+    fn scan_games(&self) -> ScanResult {
+        let mut result = ScanResult::default();
+        for folder in self.game_folders() {
+            if let Some(game) = self.game_for_folder(&folder, &mut result) {
+                result.games.push(game);
+            }
+        }
+        result
+    }
+}
+
+/// Expands `~/` and `%VARIABLE%`s. A leading `%PROGRAMDATA%`, `%APPDATA%`,
+/// `%LOCALAPPDATA%` or `%USERPROFILE%` comes from the same helpers the
+/// detectors use, so it follows the active Wine prefix or fixture root; other
+/// variables are read from the environment.
+fn resolve(path: &str) -> String {
+    if let Some(rest) = path.strip_prefix("~/") {
+        return home_dir().join(rest).to_string_lossy().to_string();
+    }
+    if let Some((folder, rest)) = known_folder(path) {
+        return rest
+            .split(['\\', '/'])
+            .filter(|part| !part.is_empty())
+            .fold(folder, |dir, part| dir.join(part))
+            .to_string_lossy()
+            .to_string();
+    }
+
+    let mut path = path.to_string();
+    while let Some(start) = path.find('%') {
+        let Some(len) = path[start + 1..].find('%') else {
+            break;
+        };
+        let name = &path[start + 1..start + 1 + len];
+        let Ok(value) = std::env::var(name) else {
+            break;
+        };
+        path.replace_range(start..start + len + 2, &value);
+    }
+    path
+}
+
+/// Splits `%APPDATA%\rest` and friends into the folder and the rest.
+fn known_folder(path: &str) -> Option<(PathBuf, &str)> {
+    let (name, rest) = path.strip_prefix('%')?.split_once('%')?;
+    let folder = match name.to_ascii_uppercase().as_str() {
+        "PROGRAMDATA" => program_data(),
+        "APPDATA" => app_data(),
+        "LOCALAPPDATA" => local_app_data(),
+        "USERPROFILE" => user_profile(),
+        _ => return None,
+    };
+    Some((folder, rest))
+}
+
+/// Every existing path matching `pattern`. Only the components after the
+/// first one with a wildcard are matched against the file system.
+fn expand_glob(pattern: &str) -> Vec<PathBuf> {
+    let parts: Vec<&str> = pattern.split(['\\', '/']).collect();
+    let first_wild = parts.iter().position(|p| p.contains(['*', '?'])).unwrap_or(parts.len());
+
+    let separator = if pattern.contains('\\') { "\\" } else { "/" };
+    let literal = parts[..first_wild].join(separator);
+    let base = if literal.is_empty() {
+        PathBuf::from(if pattern.starts_with('/') { "/" } else { "." })
+    } else {
+        // Windows paths resolve through the active Wine prefix.
+        host_path(&literal)
+    };
+
+    let mut matches = vec![base];
+    let mut index = first_wild;
+    while index < parts.len() {
+        let part = parts[index];
+        let mut next = Vec::new();
+        for dir in &matches {
+            if part == "**" {
+                next.extend(
+                    walkdir::WalkDir::new(dir)
+                        .into_iter()
+                        .flatten()
+                        .filter(|e| e.file_type().is_dir())
+                        .map(|e| e.into_path()),
+                );
+            } else if part.contains(['*', '?']) {
+                let Ok(entries) = fs::read_dir(dir) else {
+                    continue;
+                };
+                next.extend(
+                    entries
+                        .flatten()
+                        .filter(|e| glob_match(part, &e.file_name().to_string_lossy()))
+                        .map(|e| e.path()),
+                );
+            } else if !part.is_empty() {
+                next.push(dir.join(part));
+            } else {
+                next.push(dir.clone());
+            }
+        }
+        matches = next;
+        index += 1;
+    }
+    matches.into_iter().filter(|path| path.exists()).collect()
+}
+
+/// Case-insensitive match of one name against a pattern with `*` and `?`.
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let name: Vec<char> = name.to_lowercase().chars().collect();
+
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    n = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn touch(path: &Path, size: usize) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, vec![0u8; size]).unwrap();
+    }
+
+    fn launcher(json: &str) -> CustomLauncher {
+        parse_config(json).unwrap().remove(0)
+    }
+
+    #[test]
+    fn glob_match_handles_wildcards() {
+        assert!(glob_match("*.exe", "Game.EXE"));
+        assert!(glob_match("game-?", "game-1"));
+        assert!(!glob_match("game-?", "game-12"));
+        assert!(glob_match("unins*", "unins000.exe"));
+        assert!(glob_match("*setup*", "DXSetup.exe"));
+        assert!(glob_match("a*b*c", "aXXbYYc"));
+        assert!(!glob_match("a*b*c", "aXXbYY"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("?", ""));
+        assert!(glob_match("Café*", "CAFÉ Quest"));
+    }
+
+    #[test]
+    fn expand_glob_matches_folders() {
+        let root = TempDir::new("glob");
+        for folder in ["Games/Orbit Runner", "Games/Star Miner", "Games/notes", "Deep/a/b/game-1", "Deep/game-2"] {
+            fs::create_dir_all(root.join(folder)).unwrap();
+        }
+        let base = root.to_string_lossy().to_string();
+
+        let mut games = expand_glob(&format!("{}/Games/*r*", base));
+        games.sort();
+        assert_eq!(games, [root.join("Games/Orbit Runner"), root.join("Games/Star Miner")]);
+
+        let mut deep = expand_glob(&format!("{}/Deep/**/game-?", base));
+        deep.sort();
+        assert_eq!(deep, [root.join("Deep/a/b/game-1"), root.join("Deep/game-2")]);
+
+        assert_eq!(expand_glob(&format!("{}/Games/notes", base)), [root.join("Games/notes")]);
+        assert!(expand_glob(&format!("{}/Missing/*", base)).is_empty());
+    }
+
+    #[test]
+    fn pick_executable_prefers_shallow_then_large() {
+        let root = TempDir::new("pick");
+        touch(&root.join("unins000.exe"), 500);
+        touch(&root.join("Setup.exe"), 500);
+        touch(&root.join("small.exe"), 10);
+        touch(&root.join("game.exe"), 100);
+        touch(&root.join("readme.txt"), 1000);
+        touch(&root.join("bin").join("huge.exe"), 10_000);
+
+        let default = launcher(r#"{"launchers": [{"id": "a", "name": "A"}]}"#);
+        assert_eq!(default.pick_executable(&root), Some(root.join("game.exe")));
+
+        let excluded = launcher(r#"{"launchers": [{"id": "a", "name": "A", "executable": {"exclude": ["game*", "small*"]}}]}"#);
+        assert_eq!(excluded.pick_executable(&root), Some(root.join("bin").join("huge.exe")));
+
+        let shallow = launcher(
+            r#"{"launchers": [{"id": "a", "name": "A", "executable": {"include": ["*.exe"], "exclude": ["game*", "small*"], "max_depth": 1}}]}"#,
+        );
+        assert_eq!(shallow.pick_executable(&root), None);
+
+        let text = launcher(r#"{"launchers": [{"id": "a", "name": "A", "executable": {"include": ["*.txt"]}}]}"#);
+        assert_eq!(text.pick_executable(&root), Some(root.join("readme.txt")));
+    }
+
+    #[test]
+    fn resolves_windows_folders_through_the_fixture_root() {
+        let root = TempDir::new("resolve");
+        fs::create_dir_all(root.join("drive_c").join("ProgramData")).unwrap();
+        crate::sandbox::with_root(root.path(), || {
+            assert_eq!(
+                PathBuf::from(resolve("%ProgramData%\\Studio\\Games")),
+                root.join("drive_c").join("ProgramData").join("Studio").join("Games")
+            );
+            assert_eq!(PathBuf::from(resolve("~/studio")), root.join("home").join("studio"));
+        })
+        .unwrap();
+    }
+}
@@ -0,0 +1,250 @@
+// EA App installs.
+//
+// Every EA game leaves __Installer\installerdata.xml in its install folder
+// with the content ids, localized titles and the runtime executable. The EA
+// App also keeps copies under %PROGRAMDATA%\EA Desktop\InstallData, which is
+// how games outside the default library folder are found. Their executable
+// path is usually relative to a registry value, e.g.
+// `[HKEY_LOCAL_MACHINE\SOFTWARE\Respawn\Apex\Install Dir]r5apex.exe`.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+use super::{host_path, path_exists, program_data, registry_key_exists, uri_launch_available, Launcher, ScanResult};
+use crate::registry::{self, Hive};
+use crate::GameInfo;
+
+const LAUNCHER_ID: &str = "ea";
+const DEFAULT_LIBRARY: &str = "C:\\Program Files\\EA Games";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InstallerData {
+    pub content_ids: Vec<String>,
+    pub title: Option<String>,
+    /// Runtime launcher file paths, possibly prefixed with a `[HKEY_...]` reference.
+    pub executables: Vec<String>,
+}
+
+impl InstallerData {
+    /// The EA App understands the Origin launch URI; it takes the offer ids
+    /// from the manifest, not our own game id.
+    pub fn launch_uri(&self) -> Option<String> {
+        if self.content_ids.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "origin2://game/launch?offerIds={}&autoDownload=1",
+            self.content_ids.join(",")
+        ))
+    }
+}
+
+pub fn install_data_dir() -> PathBuf {
+    program_data().join("EA Desktop").join("InstallData")
+}
+
+/// Parses installerdata.xml. Handles both the current `<DiPManifest>` layout
+/// (`gameTitles/gameTitle`) and the older `metadata/localeInfo/title` one.
+pub fn parse_installer_data(xml: &str) -> Result<InstallerData, String> {
+    let doc = roxmltree::Document::parse(xml).map_err(|e| e.to_string())?;
+    let mut data = InstallerData::default();
+
+    let mut titles: Vec<(String, String)> = Vec::new();
+    for node in doc.descendants().filter(|n| n.is_element()) {
+        match node.tag_name().name() {
+            "contentID" => {
+                if let Some(id) = node.text().map(str::trim).filter(|id| !id.is_empty()) {
+                    if !data.content_ids.iter().any(|known| known == id) {
+                        data.content_ids.push(id.to_string());
+                    }
+                }
+            }
+            "gameTitle" | "title" => {
+                let locale = node
+                    .attribute("locale")
+                    .or_else(|| node.parent_element().and_then(|p| p.attribute("locale")))
+                    .unwrap_or("");
+                if let Some(title) = node.text().map(str::trim).filter(|t| !t.is_empty()) {
+                    titles.push((locale.to_string(), title.to_string()));
+                }
+            }
+            "filePath" if node.ancestors().any(|a| a.has_tag_name("runtime")) => {
+                if let Some(path) = node.text().map(str::trim).filter(|p| !p.is_empty()) {
+                    data.executables.push(path.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    data.title = titles
+        .iter()
+        .find(|(locale, _)| locale.eq_ignore_ascii_case("en_US"))
+        .or(titles.first())
+        .map(|(_, title)| title.clone());
+
+    Ok(data)
+}
+
+/// Splits `[HKEY_LOCAL_MACHINE\Key\Value]rest` into the registry reference and
+/// the remainder. Plain paths have no reference.
+pub fn split_registry_reference(file_path: &str) -> (Option<&str>, &str) {
+    if let Some(rest) = file_path.strip_prefix('[') {
+        if let Some(end) = rest.find(']') {
+            return (Some(&rest[..end]), &rest[end + 1..]);
+        }
+    }
+    (None, file_path)
+}
+
+/// Reads the value named by a `HKEY_LOCAL_MACHINE\Key\Value` reference,
+/// looking in the 32-bit view as well since most EA keys live there.
+fn resolve_registry_reference(reference: &str) -> Option<String> {
+    let (hive, rest) = Hive::split_path(reference)?;
+    let (key, value) = rest.rsplit_once('\\')?;
+
+    let wow_key = key.replacen("SOFTWARE\\", "SOFTWARE\\WOW6432Node\\", 1);
+    [key, wow_key.as_str()]
+        .iter()
+        .find_map(|k| registry::get_string(hive, k, value))
+}
+
+/// Candidate manifests: every `<library>\<game>\__Installer\installerdata.xml`
+/// in the default library plus every copy under InstallData.
+fn find_manifests() -> Vec<PathBuf> {
+    let mut manifests = Vec::new();
+
+    if let Ok(entries) = fs::read_dir(host_path(DEFAULT_LIBRARY)) {
+        for entry in entries.flatten() {
+            let manifest = entry.path().join("__Installer").join("installerdata.xml");
+            if manifest.is_file() {
+                manifests.push(manifest);
+            }
+        }
+    }
+
+    manifests.extend(
+        WalkDir::new(install_data_dir())
+            .max_depth(4)
+            .into_iter()
+            .flatten()
+            .filter(|entry| entry.file_name().eq_ignore_ascii_case("installerdata.xml"))
+            .map(|entry| entry.into_path()),
+    );
+
+    manifests
+}
+
+/// The install folder a manifest belongs to: its grandparent when it sits in
+/// `__Installer`, otherwise whatever the runtime registry reference points at.
+fn install_dir_for(manifest_path: &Path, data: &InstallerData) -> Option<PathBuf> {
+    let parent = manifest_path.parent()?;
+    if parent.file_name().is_some_and(|name| name.eq_ignore_ascii_case("__Installer")) {
+        return parent.parent().map(Path::to_path_buf);
+    }
+
+    data.executables.iter().find_map(|file_path| {
+        let (reference, _) = split_registry_reference(file_path);
+        resolve_registry_reference(reference?).map(|dir| host_path(&dir))
+    })
+}
+
+pub fn scan() -> ScanResult {
+    scan_manifests(&find_manifests())
+}
+
+pub fn scan_manifests(manifests: &[PathBuf]) -> ScanResult {
+    let mut result = ScanResult::default();
+    let mut seen: HashSet<String> = HashSet::new();
+
+    for manifest_path in manifests {
+        let data = match fs::read_to_string(manifest_path)
+            .map_err(|e| e.to_string())
+            .and_then(|xml| parse_installer_data(&xml))
+        {
+            Ok(data) => data,
+            Err(e) => {
+                println!("Failed to read {}: {}", manifest_path.display(), e);
+                continue;
+            }
+        };
+
+        let Some(offer_id) = data.content_ids.first().cloned() else {
+            continue;
+        };
+        // The same game shows up in its install folder and in InstallData.
+        if !seen.insert(offer_id.clone()) {
+            continue;
+        }
+
+        let name = data.title.clone().unwrap_or_else(|| offer_id.clone());
+        let Some(install_dir) = install_dir_for(manifest_path, &data) else {
+            result.skip(LAUNCHER_ID, &offer_id, &name, "Install folder could not be resolved");
+            continue;
+        };
+        if !install_dir.is_dir() {
+            result.skip(
+                LAUNCHER_ID,
+                &offer_id,
+                &name,
+                format!("Install folder missing: {}", install_dir.display()),
+            );
+            continue;
+        }
+
+        let executable = data
+            .executables
+            .first()
+            .map(|file_path| split_registry_reference(file_path).1.trim_start_matches(['\\', '/']).to_string())
+            .unwrap_or_default();
+        if executable.is_empty() && !uri_launch_available() {
+            result.skip(LAUNCHER_ID, &offer_id, &name, "No runtime executable in installerdata.xml");
+            continue;
+        }
+
+        result.games.push(GameInfo {
+            id: offer_id,
+            name,
+            executable,
+            install_path: install_dir.to_string_lossy().to_string(),
+            launcher_id: LAUNCHER_ID.to_string(),
+            launch_uri: data.launch_uri(),
+            ..Default::default()
+        });
+    }
+
+    result
+}
+
+pub struct Ea;
+
+impl Launcher for Ea {
+    fn id(&self) -> &str {
+        LAUNCHER_ID
+    }
+
+    fn name(&self) -> &str {
+        "EA App"
+    }
+
+    fn icon(&self) -> &str {
+        "⚡"
+    }
+
+    fn detect(&self) -> bool {
+        registry_key_exists("SOFTWARE\\WOW6432Node\\Electronic Arts\\EA Desktop")
+            || path_exists("C:\\Program Files\\Electronic Arts\\EA Desktop")
+            || path_exists("C:\\Program Files (x86)\\Electronic Arts\\EA Desktop")
+    }
+
+    fn install_path(&self) -> Option<String> {
+        Some("C:\\Program Files\\Electronic Arts\\EA Desktop".to_string())
+    }
+
+    fn scan_games(&self) -> ScanResult {
+        scan()
+    }
+}
@@ -0,0 +1,395 @@
+// Epic Games Store installs, read from the per-game JSON `.item` manifests in
+// %PROGRAMDATA%\Epic\EpicGamesLauncher\Data\Manifests and cross-checked
+// against %PROGRAMDATA%\Epic\UnrealEngineLauncher\LauncherInstalled.dat.
+//
+// The two drift apart after drive moves or failed uninstalls, so every game
+// records whether they agree and only installs that still exist on disk and
+// have a manifest are imported.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::{host_path, path_exists, program_data, registry_key_exists, Launcher, ScanResult};
+use crate::GameInfo;
+
+const LAUNCHER_ID: &str = "epic";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct EpicManifest {
+    pub display_name: String,
+    pub install_location: String,
+    pub launch_executable: String,
+    pub launch_command: String,
+    pub app_name: String,
+    pub catalog_namespace: String,
+    pub catalog_item_id: String,
+    pub main_game_app_name: String,
+    pub app_categories: Vec<String>,
+    #[serde(rename = "bIsIncompleteInstall")]
+    pub is_incomplete_install: bool,
+    #[serde(rename = "bIsExecutable")]
+    pub is_executable: Option<bool>,
+}
+
+impl EpicManifest {
+    /// Add-ons carry their parent game's AppName in MainGameAppName.
+    pub fn is_dlc(&self) -> bool {
+        let child_of_other = !self.main_game_app_name.is_empty() && self.main_game_app_name != self.app_name;
+        child_of_other || self.app_categories.iter().any(|c| c.eq_ignore_ascii_case("addons"))
+    }
+
+    /// `com.epicgames.launcher://` URI that starts the game through the
+    /// launcher, which handles authentication and cloud saves.
+    pub fn launch_uri(&self) -> String {
+        format!(
+            "com.epicgames.launcher://apps/{}%3A{}%3A{}?action=launch&silent=true",
+            self.catalog_namespace, self.catalog_item_id, self.app_name
+        )
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct LauncherInstalled {
+    #[serde(default)]
+    installation_list: Vec<InstalledEntry>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct InstalledEntry {
+    pub install_location: String,
+    pub namespace_id: String,
+    pub item_id: String,
+    pub artifact_id: String,
+    pub app_version: String,
+    pub app_name: String,
+}
+
+/// How the manifest and LauncherInstalled.dat agree about one install.
+/// Imported games carry one of the first three; `DatOnly` and
+/// `InstallDirMissing` installs are skipped and carry it on the skip entry,
+/// as do `LocationMismatch` installs whose manifest folder is gone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallConsistency {
+    /// Both sources list the game at the same location.
+    Consistent,
+    /// Both list the game but at different locations, typically after a drive move.
+    LocationMismatch,
+    /// Only the `.item` manifest knows about the game.
+    ManifestOnly,
+    /// Only LauncherInstalled.dat knows about the game.
+    DatOnly,
+    /// The install folder no longer exists.
+    InstallDirMissing,
+}
+
+pub fn manifests_dir() -> PathBuf {
+    program_data()
+        .join("Epic")
+        .join("EpicGamesLauncher")
+        .join("Data")
+        .join("Manifests")
+}
+
+pub fn launcher_installed_path() -> PathBuf {
+    program_data()
+        .join("Epic")
+        .join("UnrealEngineLauncher")
+        .join("LauncherInstalled.dat")
+}
+
+pub fn read_launcher_installed(path: &Path) -> Result<Vec<InstalledEntry>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let installed: LauncherInstalled =
+        serde_json::from_str(&text).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+    Ok(installed.installation_list)
+}
+
+pub fn read_manifests(dir: &Path) -> Vec<(PathBuf, Result<EpicManifest, String>)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        println!("Epic manifests folder not found: {}", dir.display());
+        return Vec::new();
+    };
+
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("item")))
+        .collect();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let manifest = fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|text| serde_json::from_str::<EpicManifest>(&text).map_err(|e| e.to_string()));
+            (path, manifest)
+        })
+        .collect()
+}
+
+pub fn scan() -> ScanResult {
+    scan_sources(&manifests_dir(), &launcher_installed_path())
+}
+
+pub fn scan_sources(manifests_dir: &Path, launcher_installed: &Path) -> ScanResult {
+    let mut result = ScanResult::default();
+
+    // A missing or broken dat file must not hide games that have manifests,
+    // but without it every game can only be "manifest only".
+    let installed = match read_launcher_installed(launcher_installed) {
+        Ok(list) => Some(list),
+        Err(e) => {
+            println!("{}", e);
+            None
+        }
+    };
+    let mut dat_entries: HashMap<String, InstalledEntry> = installed
+        .iter()
+        .flatten()
+        .map(|entry| (entry.app_name.clone(), entry.clone()))
+        .collect();
+
+    for (path, manifest) in read_manifests(manifests_dir) {
+        let manifest = match manifest {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                let file = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+                result.skip(LAUNCHER_ID, &file, &file, format!("Unreadable manifest: {}", e));
+                continue;
+            }
+        };
+
+        let name = if manifest.display_name.is_empty() { &manifest.app_name } else { &manifest.display_name };
+        let dat_entry = dat_entries.remove(&manifest.app_name);
+
+        if manifest.is_incomplete_install {
+            result.skip(LAUNCHER_ID, &manifest.app_name, name, "Incomplete install");
+            continue;
+        }
+        if manifest.is_dlc() {
+            result.skip(LAUNCHER_ID, &manifest.app_name, name, format!("DLC for {}", manifest.main_game_app_name));
+            continue;
+        }
+        if manifest.launch_executable.is_empty() || manifest.is_executable == Some(false) {
+            result.skip(LAUNCHER_ID, &manifest.app_name, name, "No launch executable");
+            continue;
+        }
+
+        let (install_path, consistency) = reconcile(&manifest, dat_entry.as_ref());
+        if consistency == InstallConsistency::InstallDirMissing {
+            result.skip_with_consistency(
+                LAUNCHER_ID,
+                &manifest.app_name,
+                name,
+                Some(consistency),
+                format!("Install folder missing: {}", manifest.install_location),
+            );
+            continue;
+        }
+        // The launcher runs the game from the manifest's folder, so a game
+        // that only exists where LauncherInstalled.dat says would not start.
+        if consistency == InstallConsistency::LocationMismatch && !host_path(&install_path).is_dir() {
+            let dat_location = dat_entry.as_ref().map(|e| e.install_location.as_str()).unwrap_or_default();
+            result.skip_with_consistency(
+                LAUNCHER_ID,
+                &manifest.app_name,
+                name,
+                Some(consistency),
+                format!(
+                    "Install folder missing: {} (LauncherInstalled.dat lists {})",
+                    manifest.install_location, dat_location
+                ),
+            );
+            continue;
+        }
+
+        result.games.push(GameInfo {
+            id: manifest.app_name.clone(),
+            name: name.clone(),
+            executable: manifest.launch_executable.clone(),
+            install_path,
+            launcher_id: LAUNCHER_ID.to_string(),
+            launch_uri: Some(manifest.launch_uri()),
+            install_consistency: Some(consistency),
+            ..Default::default()
+        });
+    }
+
+    // Whatever is left in the dat file has no manifest: the launcher would not
+    // start it, so report it instead of importing a ghost entry.
+    let mut leftovers: Vec<InstalledEntry> = dat_entries.into_values().collect();
+    leftovers.sort_by(|a, b| a.app_name.cmp(&b.app_name));
+    for entry in leftovers {
+        let (consistency, reason) = if host_path(&entry.install_location).is_dir() {
+            (InstallConsistency::DatOnly, "Listed in LauncherInstalled.dat but has no manifest")
+        } else {
+            (InstallConsistency::InstallDirMissing, "Listed in LauncherInstalled.dat but the install folder is missing")
+        };
+        result.skip_with_consistency(LAUNCHER_ID, &entry.app_name, &entry.app_name, Some(consistency), reason);
+    }
+
+    result
+}
+
+/// Picks the install folder to use and classifies how the two sources agree.
+/// The folder is always the manifest's; on a mismatch it may not exist.
+fn reconcile(manifest: &EpicManifest, dat_entry: Option<&InstalledEntry>) -> (String, InstallConsistency) {
+    let manifest_dir_exists = host_path(&manifest.install_location).is_dir();
+
+    let Some(dat_entry) = dat_entry else {
+        let consistency = if manifest_dir_exists {
+            InstallConsistency::ManifestOnly
+        } else {
+            InstallConsistency::InstallDirMissing
+        };
+        return (manifest.install_location.clone(), consistency);
+    };
+
+    if same_path(&manifest.install_location, &dat_entry.install_location) {
+        let consistency = if manifest_dir_exists {
+            InstallConsistency::Consistent
+        } else {
+            InstallConsistency::InstallDirMissing
+        };
+        return (manifest.install_location.clone(), consistency);
+    }
+
+    if manifest_dir_exists || host_path(&dat_entry.install_location).is_dir() {
+        (manifest.install_location.clone(), InstallConsistency::LocationMismatch)
+    } else {
+        (manifest.install_location.clone(), InstallConsistency::InstallDirMissing)
+    }
+}
+
+fn same_path(a: &str, b: &str) -> bool {
+    let normalize = |p: &str| p.replace('/', "\\").trim_end_matches('\\').to_lowercase();
+    normalize(a) == normalize(b)
+}
+
+pub struct Epic;
+
+impl Launcher for Epic {
+    fn id(&self) -> &str {
+        LAUNCHER_ID
+    }
+
+    fn name(&self) -> &str {
+        "Epic Games"
+    }
+
+    fn icon(&self) -> &str {
+        "🎮"
+    }
+
+    fn detect(&self) -> bool {
+        registry_key_exists("SOFTWARE\\WOW6432Node\\Epic Games\\EpicGamesLauncher")
+            || path_exists("C:\\Program Files (x86)\\Epic Games\\Launcher")
+            || path_exists("C:\\Program Files\\Epic Games\\Launcher")
+    }
+
+    fn install_path(&self) -> Option<String> {
+        Some("C:\\Program Files (x86)\\Epic Games\\Launcher".to_string())
+    }
+
+    fn scan_games(&self) -> ScanResult {
+        scan()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn manifest(dir: &Path, app_name: &str, install_location: &Path) {
+        let manifest = serde_json::json!({
+            "DisplayName": app_name,
+            "AppName": app_name,
+            "InstallLocation": install_location,
+            "LaunchExecutable": "Game.exe",
+            "CatalogNamespace": "ns",
+            "CatalogItemId": "item",
+        });
+        fs::write(dir.join(format!("{}.item", app_name)), manifest.to_string()).unwrap();
+    }
+
+    #[test]
+    fn records_why_installs_were_skipped() {
+        let root = TempDir::new("epic");
+        let manifests = root.join("Manifests");
+        fs::create_dir_all(&manifests).unwrap();
+        for game in ["Both", "ManifestOnly", "DatOnly"] {
+            fs::create_dir_all(root.join("Games").join(game)).unwrap();
+        }
+        let games = root.join("Games");
+
+        manifest(&manifests, "Both", &games.join("Both"));
+        manifest(&manifests, "ManifestOnly", &games.join("ManifestOnly"));
+        manifest(&manifests, "Gone", &games.join("Gone"));
+        let dat = serde_json::json!({ "InstallationList": [
+            { "AppName": "Both", "InstallLocation": games.join("Both") },
+            { "AppName": "Gone", "InstallLocation": games.join("Gone") },
+            { "AppName": "DatOnly", "InstallLocation": games.join("DatOnly") },
+            { "AppName": "DatGone", "InstallLocation": games.join("DatGone") },
+        ]});
+        fs::write(root.join("LauncherInstalled.dat"), dat.to_string()).unwrap();
+
+        let result = scan_sources(&manifests, &root.join("LauncherInstalled.dat"));
+        let games: Vec<(&str, Option<InstallConsistency>)> =
+            result.games.iter().map(|g| (g.id.as_str(), g.install_consistency)).collect();
+        assert_eq!(
+            games,
+            [("Both", Some(InstallConsistency::Consistent)), ("ManifestOnly", Some(InstallConsistency::ManifestOnly))]
+        );
+        let skipped: Vec<(&str, Option<InstallConsistency>)> =
+            result.skipped.iter().map(|g| (g.id.as_str(), g.install_consistency)).collect();
+        assert_eq!(
+            skipped,
+            [
+                ("Gone", Some(InstallConsistency::InstallDirMissing)),
+                ("DatGone", Some(InstallConsistency::InstallDirMissing)),
+                ("DatOnly", Some(InstallConsistency::DatOnly)),
+            ]
+        );
+    }
+
+    #[test]
+    fn moved_installs_use_the_manifest_folder() {
+        let root = TempDir::new("epic-moved");
+        let manifests = root.join("Manifests");
+        fs::create_dir_all(&manifests).unwrap();
+        let games = root.join("Games");
+        fs::create_dir_all(games.join("Kept")).unwrap();
+        fs::create_dir_all(games.join("Restored")).unwrap();
+
+        // Kept was moved and the manifest followed; Restored only exists
+        // where the stale dat entry points.
+        manifest(&manifests, "Kept", &games.join("Kept"));
+        manifest(&manifests, "Restored", &games.join("Moved"));
+        let dat = serde_json::json!({ "InstallationList": [
+            { "AppName": "Kept", "InstallLocation": root.join("Old").join("Kept") },
+            { "AppName": "Restored", "InstallLocation": games.join("Restored") },
+        ]});
+        fs::write(root.join("LauncherInstalled.dat"), dat.to_string()).unwrap();
+
+        let result = scan_sources(&manifests, &root.join("LauncherInstalled.dat"));
+        assert_eq!(result.games.len(), 1);
+        assert_eq!(result.games[0].id, "Kept");
+        assert_eq!(result.games[0].install_path, games.join("Kept").to_string_lossy());
+        assert_eq!(result.games[0].install_consistency, Some(InstallConsistency::LocationMismatch));
+
+        assert_eq!(result.skipped.len(), 1);
+        let skipped = &result.skipped[0];
+        assert_eq!(skipped.id, "Restored");
+        assert_eq!(skipped.install_consistency, Some(InstallConsistency::LocationMismatch));
+        assert!(skipped.reason.contains(&games.join("Moved").to_string_lossy().to_string()));
+    }
+}
@@ -0,0 +1,319 @@
+// GOG games, from two sources:
+//
+// - GOG Galaxy 2's SQLite database at
+//   %PROGRAMDATA%\GOG.com\Galaxy\storage\galaxy-2.0.db. Installed products
+//   come from InstalledBaseProducts, titles from LimitedDetails and the launch
+//   command from the primary play task.
+// - Offline installer installs, registered under
+//   HKLM\SOFTWARE\WOW6432Node\GOG.com\Games\<id> with a goggame-<id>.info
+//   file in the game folder. These work without Galaxy being installed.
+//
+// Both launch the game executable directly; no launcher is involved.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rusqlite::{Connection, OpenFlags};
+use serde::Deserialize;
+
+use super::{host_path, path_exists, program_data, registry_key_exists, Launcher, ScanResult};
+use crate::registry::{self, Hive};
+use crate::GameInfo;
+
+const LAUNCHER_ID: &str = "gog";
+const GAMES_KEY: &str = "SOFTWARE\\WOW6432Node\\GOG.com\\Games";
+
+#[derive(Debug, Clone, Default)]
+pub struct GalaxyProduct {
+    pub product_id: String,
+    pub title: Option<String>,
+    pub install_path: String,
+    pub play_task: Option<PlayTask>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PlayTask {
+    pub executable: String,
+    pub arguments: String,
+    pub working_dir: Option<String>,
+}
+
+pub fn database_path() -> PathBuf {
+    program_data()
+        .join("GOG.com")
+        .join("Galaxy")
+        .join("storage")
+        .join("galaxy-2.0.db")
+}
+
+pub fn read_products(db_path: &Path) -> Result<Vec<GalaxyProduct>, String> {
+    let conn = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX)
+        .map_err(|e| format!("Failed to open {}: {}", db_path.display(), e))?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT ibp.productId, ibp.installationPath, \
+                    (SELECT ld.title FROM LimitedDetails ld WHERE ld.productId = ibp.productId LIMIT 1) \
+             FROM InstalledBaseProducts ibp \
+             ORDER BY ibp.productId",
+        )
+        .map_err(|e| format!("Failed to query installed products: {}", e))?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(GalaxyProduct {
+                product_id: row.get::<_, i64>(0)?.to_string(),
+                install_path: row.get(1)?,
+                title: row.get(2)?,
+                play_task: None,
+            })
+        })
+        .map_err(|e| format!("Failed to read installed products: {}", e))?;
+
+    let mut products: Vec<GalaxyProduct> = rows.flatten().collect();
+    let has_working_dir = column_exists(&conn, "PlayTaskLaunchParameters", "workingDirectory");
+    for product in &mut products {
+        product.play_task = read_primary_play_task(&conn, &product.product_id, has_working_dir);
+    }
+    Ok(products)
+}
+
+/// The primary play task of a product, i.e. what Galaxy's Play button runs.
+fn read_primary_play_task(conn: &Connection, product_id: &str, has_working_dir: bool) -> Option<PlayTask> {
+    let working_dir = if has_working_dir { "ptlp.workingDirectory" } else { "NULL" };
+    let sql = format!(
+        "SELECT ptlp.executablePath, ptlp.commandLineArgs, {} \
+         FROM PlayTasks pt \
+         JOIN PlayTaskLaunchParameters ptlp ON ptlp.playTaskId = pt.id \
+         WHERE pt.gameReleaseKey = ?1 \
+         ORDER BY pt.isPrimary DESC, pt.\"order\" ASC \
+         LIMIT 1",
+        working_dir
+    );
+
+    conn.query_row(&sql, [format!("gog_{}", product_id)], |row| {
+        Ok(PlayTask {
+            executable: row.get(0)?,
+            arguments: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+            working_dir: row.get::<_, Option<String>>(2)?.filter(|dir| !dir.is_empty()),
+        })
+    })
+    .ok()
+}
+
+fn column_exists(conn: &Connection, table: &str, column: &str) -> bool {
+    let Ok(mut stmt) = conn.prepare(&format!("PRAGMA table_info({})", table)) else {
+        return false;
+    };
+    let Ok(names) = stmt.query_map([], |row| row.get::<_, String>(1)) else {
+        return false;
+    };
+    names.flatten().any(|name| name.eq_ignore_ascii_case(column))
+}
+
+/// Galaxy games first, then standalone installs Galaxy does not know about.
+pub fn scan() -> ScanResult {
+    let mut result = scan_database(&database_path());
+
+    let known: HashSet<String> = result.games.iter().map(|g| g.id.clone()).collect();
+    let standalone = scan_standalone(&read_registry_installs());
+    result.games.extend(standalone.games.into_iter().filter(|g| !known.contains(&g.id)));
+    result.skipped.extend(standalone.skipped.into_iter().filter(|g| !known.contains(&g.id)));
+    result
+}
+
+pub fn scan_database(db_path: &Path) -> ScanResult {
+    let mut result = ScanResult::default();
+
+    let products = match read_products(db_path) {
+        Ok(products) => products,
+        Err(e) => {
+            println!("{}", e);
+            return result;
+        }
+    };
+
+    for product in products {
+        let name = product.title.clone().unwrap_or_else(|| format!("GOG {}", product.product_id));
+
+        let Some(task) = product.play_task else {
+            result.skip(LAUNCHER_ID, &product.product_id, &name, "No play task");
+            continue;
+        };
+        if !host_path(&product.install_path).is_dir() {
+            result.skip(
+                LAUNCHER_ID,
+                &product.product_id,
+                &name,
+                format!("Install folder missing: {}", product.install_path),
+            );
+            continue;
+        }
+
+        result.games.push(GameInfo {
+            id: product.product_id.clone(),
+            name,
+            executable: task.executable,
+            install_path: product.install_path,
+            launcher_id: LAUNCHER_ID.to_string(),
+            launch_options: Some(task.arguments).filter(|args| !args.is_empty()),
+            working_dir: task.working_dir,
+            ..Default::default()
+        });
+    }
+
+    result
+}
+
+/// One `HKLM\...\GOG.com\Games\<id>` key written by an offline installer.
+#[derive(Debug, Clone, Default)]
+pub struct RegistryInstall {
+    pub game_id: String,
+    pub name: String,
+    pub path: String,
+}
+
+/// Contents of goggame-<id>.info in the game folder.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GameInfoFile {
+    pub game_id: String,
+    pub root_game_id: String,
+    pub name: String,
+    pub play_tasks: Vec<InfoPlayTask>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct InfoPlayTask {
+    pub is_primary: bool,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub category: String,
+    pub path: String,
+    pub arguments: String,
+    pub working_dir: String,
+}
+
+impl GameInfoFile {
+    /// The task GOG's own shortcut would run: the primary file task, or the
+    /// first file task in the "game" category.
+    pub fn primary_task(&self) -> Option<&InfoPlayTask> {
+        let file_tasks = || self.play_tasks.iter().filter(|t| t.kind == "FileTask" && !t.path.is_empty());
+        file_tasks()
+            .find(|t| t.is_primary)
+            .or_else(|| file_tasks().find(|t| t.category == "game"))
+    }
+}
+
+pub fn read_registry_installs() -> Vec<RegistryInstall> {
+    registry::subkeys(Hive::LocalMachine, GAMES_KEY)
+        .into_iter()
+        .filter_map(|id| {
+            let key = format!("{}\\{}", GAMES_KEY, id);
+            Some(RegistryInstall {
+                game_id: registry::get_string(Hive::LocalMachine, &key, "gameID").unwrap_or_else(|| id.clone()),
+                name: registry::get_string(Hive::LocalMachine, &key, "gameName").unwrap_or_default(),
+                path: registry::get_string(Hive::LocalMachine, &key, "path")?,
+            })
+        })
+        .collect()
+}
+
+pub fn read_info_file(install_path: &Path, game_id: &str) -> Result<GameInfoFile, String> {
+    let path = install_path.join(format!("goggame-{}.info", game_id));
+    let text = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    // Some installers write a UTF-8 BOM.
+    let text = text.trim_start_matches('\u{feff}');
+    serde_json::from_str(text).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+pub fn scan_standalone(installs: &[RegistryInstall]) -> ScanResult {
+    let mut result = ScanResult::default();
+
+    for install in installs {
+        let install_path = host_path(&install.path);
+        if !install_path.is_dir() {
+            result.skip(
+                LAUNCHER_ID,
+                &install.game_id,
+                &install.name,
+                format!("Install folder missing: {}", install.path),
+            );
+            continue;
+        }
+
+        let info = match read_info_file(&install_path, &install.game_id) {
+            Ok(info) => info,
+            Err(e) => {
+                result.skip(LAUNCHER_ID, &install.game_id, &install.name, e);
+                continue;
+            }
+        };
+
+        // DLC ship their own .info file pointing at the base game.
+        if !info.root_game_id.is_empty() && info.root_game_id != install.game_id {
+            result.skip(LAUNCHER_ID, &install.game_id, &install.name, format!("DLC for {}", info.root_game_id));
+            continue;
+        }
+
+        let name = if info.name.is_empty() { install.name.clone() } else { info.name.clone() };
+        let Some(task) = info.primary_task() else {
+            result.skip(LAUNCHER_ID, &install.game_id, &name, "No play task in goggame info");
+            continue;
+        };
+
+        // Galaxy stores absolute play task paths; the .info file has them
+        // relative to the install folder. Resolve them against the install
+        // folder so both branches hand out absolute Windows paths, which
+        // `wine::scan_prefix` maps for games in a prefix.
+        let in_install = |relative: &str| format!("{}\\{}", install.path.trim_end_matches(['/', '\\']), relative);
+        let working_dir = Some(task.working_dir.as_str()).filter(|dir| !dir.is_empty()).map(&in_install);
+
+        result.games.push(GameInfo {
+            id: install.game_id.clone(),
+            name,
+            executable: in_install(&task.path),
+            install_path: install.path.clone(),
+            launcher_id: LAUNCHER_ID.to_string(),
+            launch_options: Some(task.arguments.clone()).filter(|args| !args.is_empty()),
+            working_dir,
+            ..Default::default()
+        });
+    }
+
+    result
+}
+
+pub struct Gog;
+
+impl Launcher for Gog {
+    fn id(&self) -> &str {
+        LAUNCHER_ID
+    }
+
+    fn name(&self) -> &str {
+        "GOG Galaxy"
+    }
+
+    fn icon(&self) -> &str {
+        "🌟"
+    }
+
+    fn detect(&self) -> bool {
+        // GOG Galaxy, or games from GOG's offline installers
+        registry_key_exists("SOFTWARE\\WOW6432Node\\GOG.com\\GalaxyClient")
+            || registry_key_exists(GAMES_KEY)
+            || path_exists("C:\\Program Files (x86)\\GOG Galaxy")
+            || path_exists("C:\\Program Files\\GOG Galaxy")
+    }
+
+    fn install_path(&self) -> Option<String> {
+        Some("C:\\Program Files (x86)\\GOG Galaxy".to_string())
+    }
+
+    fn scan_games(&self) -> ScanResult {
+        scan()
+    }
+}
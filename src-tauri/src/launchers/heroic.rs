@@ -0,0 +1,356 @@
+// Heroic Games Launcher libraries (Linux and Steam Deck).
+//
+// Heroic wraps three stores, each with its own install list under
+// ~/.config/heroic (or the Flatpak equivalent):
+//   legendaryConfig/legendary/installed.json   Epic, via legendary
+//   gog_store/installed.json + library.json    GOG
+//   nile_config/nile/installed.json            Amazon, via nile
+//   sideload_apps/library.json                 games added by hand
+// Per-game Wine settings live in GamesConfig/<app_name>.json.
+//
+// Native Linux games run directly, Windows games configured with a plain
+// Wine build run through that Wine and prefix, and everything else goes
+// through Heroic with a heroic://launch/<runner>/<app_name> URI.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value as Json;
+
+use super::{gog, home_dir, Launcher, ScanResult};
+use crate::GameInfo;
+
+const LAUNCHER_ID: &str = "heroic";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LegendaryInstall {
+    pub app_name: String,
+    pub title: String,
+    pub install_path: String,
+    pub executable: String,
+    pub launch_parameters: String,
+    pub is_dlc: bool,
+    pub platform: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+struct GogInstalled {
+    installed: Vec<GogInstall>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GogInstall {
+    pub app_name: String,
+    #[serde(rename = "install_path")]
+    pub install_path: String,
+    pub platform: String,
+    #[serde(rename = "is_dlc")]
+    pub is_dlc: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct NileInstall {
+    pub id: String,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+struct SideloadLibrary {
+    games: Vec<SideloadGame>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SideloadGame {
+    pub app_name: String,
+    pub title: String,
+    pub folder_name: String,
+    pub install: SideloadInstall,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SideloadInstall {
+    pub executable: String,
+    pub platform: String,
+}
+
+/// The Wine/Proton settings Heroic stores for one game.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WineSettings {
+    pub wine_version: WineVersion,
+    pub wine_prefix: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct WineVersion {
+    pub bin: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+/// `~/.config/heroic`, or the Flatpak config folder when only that exists.
+pub fn config_dir() -> PathBuf {
+    let home = home_dir();
+    let native = home.join(".config").join("heroic");
+    let flatpak = home
+        .join(".var")
+        .join("app")
+        .join("com.heroicgameslauncher.hgl")
+        .join("config")
+        .join("heroic");
+    if !native.is_dir() && flatpak.is_dir() {
+        flatpak
+    } else {
+        native
+    }
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    let text = fs::read_to_string(path).ok()?;
+    match serde_json::from_str(&text) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            println!("Failed to parse {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+pub fn read_wine_settings(config_dir: &Path, app_name: &str) -> Option<WineSettings> {
+    let mut file: HashMap<String, Json> =
+        read_json(&config_dir.join("GamesConfig").join(format!("{}.json", app_name)))?;
+    serde_json::from_value(file.remove(app_name)?).ok()
+}
+
+/// Titles from GOG's library cache, keyed by app name.
+fn read_gog_titles(config_dir: &Path) -> HashMap<String, String> {
+    let library: Option<Json> = read_json(&config_dir.join("gog_store").join("library.json"));
+    library
+        .as_ref()
+        .and_then(|library| library.get("games"))
+        .and_then(Json::as_array)
+        .map(|games| {
+            games
+                .iter()
+                .filter_map(|game| {
+                    let app_name = game.get("app_name")?.as_str()?;
+                    let title = game.get("title")?.as_str()?;
+                    Some((app_name.to_string(), title.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Titles from nile's library, keyed by product id.
+fn read_nile_titles(config_dir: &Path) -> HashMap<String, String> {
+    let library: Option<Vec<Json>> = read_json(&config_dir.join("nile_config").join("nile").join("library.json"));
+    library
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|entry| {
+            let id = entry.get("id")?.as_str()?;
+            let title = entry.get("product")?.get("title")?.as_str()?;
+            Some((id.to_string(), title.to_string()))
+        })
+        .collect()
+}
+
+pub fn launch_uri(runner: &str, app_name: &str) -> String {
+    format!("heroic://launch/{}/{}", runner, app_name)
+}
+
+pub fn scan() -> ScanResult {
+    scan_config(&config_dir())
+}
+
+pub fn scan_config(config_dir: &Path) -> ScanResult {
+    let mut result = ScanResult::default();
+
+    let legendary: HashMap<String, LegendaryInstall> =
+        read_json(&config_dir.join("legendaryConfig").join("legendary").join("installed.json")).unwrap_or_default();
+    let mut legendary: Vec<LegendaryInstall> = legendary.into_values().collect();
+    legendary.sort_by(|a, b| a.title.cmp(&b.title));
+    for install in legendary {
+        if install.is_dlc {
+            result.skip(LAUNCHER_ID, &install.app_name, &install.title, "DLC");
+            continue;
+        }
+        push_game(
+            &mut result,
+            config_dir,
+            "legendary",
+            &install.app_name,
+            &install.title,
+            &install.install_path,
+            &install.executable,
+            &install.platform,
+            &install.launch_parameters,
+        );
+    }
+
+    let gog_titles = read_gog_titles(config_dir);
+    let gog: GogInstalled = read_json(&config_dir.join("gog_store").join("installed.json")).unwrap_or_default();
+    for install in gog.installed {
+        let title = gog_titles.get(&install.app_name).cloned().unwrap_or_else(|| install.app_name.clone());
+        if install.is_dlc {
+            result.skip(LAUNCHER_ID, &install.app_name, &title, "DLC");
+            continue;
+        }
+        // Heroic installs GOG games with the same goggame-<id>.info as the
+        // official installers.
+        let task = gog::read_info_file(Path::new(&install.install_path), &install.app_name)
+            .ok()
+            .and_then(|info| info.primary_task().cloned());
+        let (executable, arguments) = task.map(|t| (t.path, t.arguments)).unwrap_or_default();
+        push_game(
+            &mut result,
+            config_dir,
+            "gog",
+            &install.app_name,
+            &title,
+            &install.install_path,
+            &executable,
+            &install.platform,
+            &arguments,
+        );
+    }
+
+    let nile_titles = read_nile_titles(config_dir);
+    let nile: Vec<NileInstall> =
+        read_json(&config_dir.join("nile_config").join("nile").join("installed.json")).unwrap_or_default();
+    for install in nile {
+        let title = nile_titles.get(&install.id).cloned().unwrap_or_else(|| install.id.clone());
+        let executable = super::amazon::read_fuel(Path::new(&install.path))
+            .map(|fuel| fuel.main.command)
+            .unwrap_or_default();
+        push_game(&mut result, config_dir, "nile", &install.id, &title, &install.path, &executable, "windows", "");
+    }
+
+    let sideload: SideloadLibrary =
+        read_json(&config_dir.join("sideload_apps").join("library.json")).unwrap_or_default();
+    for game in sideload.games {
+        let install_path = if game.folder_name.is_empty() {
+            Path::new(&game.install.executable)
+                .parent()
+                .map(|dir| dir.to_string_lossy().to_string())
+                .unwrap_or_default()
+        } else {
+            game.folder_name.clone()
+        };
+        push_game(
+            &mut result,
+            config_dir,
+            "sideload",
+            &game.app_name,
+            &game.title,
+            &install_path,
+            &game.install.executable,
+            &game.install.platform,
+            "",
+        );
+    }
+
+    result
+}
+
+#[allow(clippy::too_many_arguments)]
+fn push_game(
+    result: &mut ScanResult,
+    config_dir: &Path,
+    runner: &str,
+    app_name: &str,
+    title: &str,
+    install_path: &str,
+    executable: &str,
+    platform: &str,
+    arguments: &str,
+) {
+    let id = format!("{}:{}", runner, app_name);
+    if !Path::new(install_path).is_dir() {
+        result.skip(LAUNCHER_ID, &id, title, format!("Install folder missing: {}", install_path));
+        return;
+    }
+
+    let mut game = GameInfo {
+        id,
+        name: title.to_string(),
+        executable: executable.to_string(),
+        install_path: install_path.to_string(),
+        launcher_id: LAUNCHER_ID.to_string(),
+        launch_uri: Some(launch_uri(runner, app_name)),
+        ..Default::default()
+    };
+
+    let is_native = platform.eq_ignore_ascii_case("linux");
+    if is_native && !executable.is_empty() {
+        game.launch_uri = None;
+        game.launch_options = Some(arguments.to_string()).filter(|args| !args.is_empty());
+    } else if let Some(wine) = read_wine_settings(config_dir, app_name)
+        .filter(|wine| wine.wine_version.kind == "wine" && !wine.wine_version.bin.is_empty())
+        .filter(|_| !executable.is_empty())
+    {
+        // Plain Wine needs nothing but the prefix, so skip Heroic entirely.
+        // Proton builds need Steam's compat environment and stay on the URI.
+        let exe_path = Path::new(install_path).join(executable);
+        game.launch_uri = None;
+        game.executable = wine.wine_version.bin;
+        game.working_dir = Some(install_path.to_string());
+        game.launch_options = Some(
+            format!(
+                "WINEPREFIX=\"{}\" %command% \"{}\" {}",
+                wine.wine_prefix,
+                exe_path.to_string_lossy(),
+                arguments
+            )
+            .trim_end()
+            .to_string(),
+        );
+    }
+
+    result.games.push(game);
+}
+
+pub struct Heroic;
+
+impl Launcher for Heroic {
+    fn id(&self) -> &str {
+        LAUNCHER_ID
+    }
+
+    fn name(&self) -> &str {
+        "Heroic Games Launcher"
+    }
+
+    fn icon(&self) -> &str {
+        "🦸"
+    }
+
+    fn detect(&self) -> bool {
+        config_dir().is_dir()
+    }
+
+    fn install_path(&self) -> Option<String> {
+        Some(config_dir().to_string_lossy().to_string())
+    }
+
+    fn scan_games(&self) -> ScanResult {
+        scan()
+    }
+
+    fn runs_in_wine(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,241 @@
+// itch app library, read from butler's SQLite database at
+// %APPDATA%\itch\db\butler.db, or ~/.config/itch/db/butler.db for the
+// native Linux app.
+//
+// Every install is a "cave". Its verdict (a JSON column written by butler
+// after scanning the install folder) lists candidate executables; the best
+// one becomes the game's executable.
+
+use std::path::{Path, PathBuf};
+
+use rusqlite::{Connection, OpenFlags};
+use serde::Deserialize;
+
+use super::{app_data, home_dir, host_path, wine, Launcher, ScanResult};
+use crate::GameInfo;
+
+const LAUNCHER_ID: &str = "itch";
+
+#[derive(Debug, Clone, Default)]
+pub struct Cave {
+    pub id: String,
+    pub title: String,
+    pub install_folder: Option<PathBuf>,
+    pub verdict: Option<Verdict>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Verdict {
+    pub base_path: String,
+    pub candidates: Vec<Candidate>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Candidate {
+    pub path: String,
+    pub depth: u32,
+    pub flavor: String,
+    pub arch: String,
+    pub size: u64,
+}
+
+impl Verdict {
+    /// Prefers executables for the platform the itch app runs on (Linux
+    /// binaries for the native app, Windows ones otherwise), then the other
+    /// platform's, then scripts in the same order, and within a flavor the
+    /// shallowest 64-bit candidate.
+    pub fn best_candidate(&self, native_app: bool) -> Option<&Candidate> {
+        let flavor_rank = |flavor: &str| match (flavor, native_app) {
+            ("windows", false) | ("native" | "linux", true) => 0,
+            ("windows", true) | ("native" | "linux", false) => 1,
+            ("script-windows", false) | ("script", true) => 2,
+            ("script-windows", true) | ("script", false) => 3,
+            ("html", _) => 4,
+            _ => 5,
+        };
+        self.candidates
+            .iter()
+            .filter(|c| !c.path.is_empty())
+            .min_by_key(|c| (flavor_rank(&c.flavor), c.depth, c.arch != "amd64"))
+    }
+}
+
+/// Whether to read the native app in `~/.config/itch`: only when the Windows
+/// app is not installed. A Wine prefix only ever holds the Windows app.
+pub fn uses_native_app() -> bool {
+    !cfg!(windows)
+        && wine::active_prefix().is_none()
+        && !app_data().join("itch").is_dir()
+        && home_dir().join(".config").join("itch").is_dir()
+}
+
+/// butler.db of the Windows app, or of the native app (see `uses_native_app`).
+pub fn database_path() -> PathBuf {
+    let dir = if uses_native_app() {
+        home_dir().join(".config").join("itch")
+    } else {
+        app_data().join("itch")
+    };
+    dir.join("db").join("butler.db")
+}
+
+pub fn read_caves(db_path: &Path) -> Result<Vec<Cave>, String> {
+    let conn = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX)
+        .map_err(|e| format!("Failed to open {}: {}", db_path.display(), e))?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT c.id, g.title, il.path, c.install_folder_name, c.verdict \
+             FROM caves c \
+             LEFT JOIN games g ON g.id = c.game_id \
+             LEFT JOIN install_locations il ON il.id = c.install_location_id \
+             ORDER BY g.title",
+        )
+        .map_err(|e| format!("Failed to query itch caves: {}", e))?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            let location: Option<String> = row.get(2)?;
+            let folder_name: Option<String> = row.get(3)?;
+            let verdict: Option<String> = row.get(4)?;
+            Ok(Cave {
+                id: row.get(0)?,
+                title: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                install_folder: location.zip(folder_name).map(|(location, folder)| host_path(&location).join(folder)),
+                verdict: verdict.and_then(|v| serde_json::from_str(&v).ok()),
+            })
+        })
+        .map_err(|e| format!("Failed to read itch caves: {}", e))?;
+
+    Ok(rows.flatten().collect())
+}
+
+pub fn scan() -> ScanResult {
+    scan_database(&database_path(), uses_native_app())
+}
+
+pub fn scan_database(db_path: &Path, native_app: bool) -> ScanResult {
+    let mut result = ScanResult::default();
+
+    let caves = match read_caves(db_path) {
+        Ok(caves) => caves,
+        Err(e) => {
+            println!("{}", e);
+            return result;
+        }
+    };
+
+    for cave in caves {
+        let name = if cave.title.is_empty() { cave.id.clone() } else { cave.title.clone() };
+
+        let Some(verdict) = &cave.verdict else {
+            result.skip(LAUNCHER_ID, &cave.id, &name, "Not scanned by the itch app yet");
+            continue;
+        };
+        let Some(candidate) = verdict.best_candidate(native_app) else {
+            result.skip(LAUNCHER_ID, &cave.id, &name, "No launchable executable");
+            continue;
+        };
+
+        // The verdict's base path is where butler actually found the files.
+        let install_folder = if verdict.base_path.is_empty() {
+            cave.install_folder.clone()
+        } else {
+            Some(host_path(&verdict.base_path))
+        };
+        let Some(install_folder) = install_folder.filter(|dir| dir.is_dir()) else {
+            result.skip(LAUNCHER_ID, &cave.id, &name, "Install folder missing");
+            continue;
+        };
+
+        result.games.push(GameInfo {
+            id: cave.id.clone(),
+            name,
+            executable: candidate.path.clone(),
+            install_path: install_folder.to_string_lossy().to_string(),
+            launcher_id: LAUNCHER_ID.to_string(),
+            ..Default::default()
+        });
+    }
+
+    result
+}
+
+pub struct Itch;
+
+impl Launcher for Itch {
+    fn id(&self) -> &str {
+        LAUNCHER_ID
+    }
+
+    fn name(&self) -> &str {
+        "itch"
+    }
+
+    fn icon(&self) -> &str {
+        "🕹️"
+    }
+
+    fn detect(&self) -> bool {
+        database_path().exists()
+    }
+
+    fn install_path(&self) -> Option<String> {
+        database_path().parent().and_then(Path::parent).map(|p| p.to_string_lossy().to_string())
+    }
+
+    fn scan_games(&self) -> ScanResult {
+        scan()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn finds_the_native_app_outside_a_prefix() {
+        let root = TempDir::new("itch");
+        let native = root.join("home").join(".config").join("itch");
+        let windows = root.join("drive_c").join("users").join("steamuser").join("AppData").join("Roaming").join("itch");
+        std::fs::create_dir_all(&native).unwrap();
+        std::fs::create_dir_all(windows.parent().unwrap()).unwrap();
+
+        let path = crate::sandbox::with_root(root.path(), database_path).unwrap();
+        assert_eq!(path, native.join("db").join("butler.db"));
+
+        // The Windows app wins when both are installed.
+        std::fs::create_dir_all(&windows).unwrap();
+        let path = crate::sandbox::with_root(root.path(), database_path).unwrap();
+        assert_eq!(path, windows.join("db").join("butler.db"));
+    }
+
+    #[test]
+    fn prefers_executables_for_the_app_platform() {
+        let candidate = |path: &str, flavor: &str, depth: u32| Candidate {
+            path: path.into(),
+            depth,
+            flavor: flavor.into(),
+            arch: "amd64".into(),
+            ..Default::default()
+        };
+        let verdict = Verdict {
+            base_path: String::new(),
+            candidates: vec![
+                candidate("start.sh", "script", 0),
+                candidate("linux/Game.x86_64", "linux", 1),
+                candidate("windows/Game.exe", "windows", 1),
+            ],
+        };
+
+        assert_eq!(verdict.best_candidate(false).unwrap().path, "windows/Game.exe");
+        assert_eq!(verdict.best_candidate(true).unwrap().path, "linux/Game.x86_64");
+
+        let scripts = Verdict { base_path: String::new(), candidates: vec![candidate("start.bat", "script-windows", 0), candidate("start.sh", "script", 0)] };
+        assert_eq!(scripts.best_candidate(false).unwrap().path, "start.bat");
+        assert_eq!(scripts.best_candidate(true).unwrap().path, "start.sh");
+    }
+}
@@ -0,0 +1,237 @@
+// Lutris library.
+//
+// Installed games are rows of the `games` table in
+// ~/.local/share/lutris/pga.db; their runner settings live in
+// ~/.config/lutris/games/<configpath>.yml. Lutris sets up Wine, DXVK and
+// everything else itself, so every game is started with
+// `lutris lutris:rungameid/<id>`.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rusqlite::{Connection, OpenFlags};
+use serde_yaml::Value as Yaml;
+
+use super::{home_dir, Launcher, ScanResult};
+use crate::GameInfo;
+
+const LAUNCHER_ID: &str = "lutris";
+const FLATPAK_ID: &str = "net.lutris.Lutris";
+
+#[derive(Debug, Clone, Default)]
+pub struct LutrisGame {
+    pub id: i64,
+    pub name: String,
+    pub slug: String,
+    pub runner: String,
+    pub directory: String,
+    pub configpath: String,
+    pub installed: bool,
+}
+
+/// The parts of a game YAML we use.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GameConfig {
+    pub exe: Option<String>,
+    pub working_dir: Option<String>,
+    pub prefix: Option<String>,
+}
+
+/// Where Lutris keeps its database and game configs, native or Flatpak.
+#[derive(Debug, Clone)]
+pub struct LutrisPaths {
+    pub database: PathBuf,
+    pub games_config_dir: PathBuf,
+    pub flatpak: bool,
+}
+
+pub fn paths() -> LutrisPaths {
+    let home = home_dir();
+    let native = LutrisPaths {
+        database: home.join(".local").join("share").join("lutris").join("pga.db"),
+        games_config_dir: home.join(".config").join("lutris").join("games"),
+        flatpak: false,
+    };
+    let flatpak_root = home.join(".var").join("app").join(FLATPAK_ID);
+    let flatpak = LutrisPaths {
+        database: flatpak_root.join("data").join("lutris").join("pga.db"),
+        games_config_dir: flatpak_root.join("config").join("lutris").join("games"),
+        flatpak: true,
+    };
+
+    if !native.database.exists() && flatpak.database.exists() {
+        flatpak
+    } else {
+        native
+    }
+}
+
+pub fn read_games(db_path: &Path) -> Result<Vec<LutrisGame>, String> {
+    let conn = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX)
+        .map_err(|e| format!("Failed to open {}: {}", db_path.display(), e))?;
+
+    let mut stmt = conn
+        .prepare("SELECT id, name, slug, runner, directory, configpath, installed FROM games ORDER BY name")
+        .map_err(|e| format!("Failed to query Lutris games: {}", e))?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(LutrisGame {
+                id: row.get(0)?,
+                name: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                slug: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                runner: row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+                directory: row.get::<_, Option<String>>(4)?.unwrap_or_default(),
+                configpath: row.get::<_, Option<String>>(5)?.unwrap_or_default(),
+                installed: row.get::<_, Option<i64>>(6)?.unwrap_or(0) != 0,
+            })
+        })
+        .map_err(|e| format!("Failed to read Lutris games: {}", e))?;
+
+    Ok(rows.flatten().collect())
+}
+
+pub fn parse_game_config(yaml: &str) -> Result<GameConfig, String> {
+    let doc: Yaml = serde_yaml::from_str(yaml).map_err(|e| e.to_string())?;
+    let game = &doc["game"];
+    let field = |key: &str| game[key].as_str().filter(|v| !v.is_empty()).map(str::to_string);
+    Ok(GameConfig {
+        exe: field("exe"),
+        working_dir: field("working_dir"),
+        prefix: field("prefix"),
+    })
+}
+
+fn read_game_config(config_dir: &Path, configpath: &str) -> Option<GameConfig> {
+    if configpath.is_empty() {
+        return None;
+    }
+    let path = config_dir.join(format!("{}.yml", configpath));
+    let text = fs::read_to_string(&path).ok()?;
+    parse_game_config(&text)
+        .map_err(|e| println!("Failed to parse {}: {}", path.display(), e))
+        .ok()
+}
+
+/// Wine prefixes of the installed Lutris games, from their game configs.
+pub fn wine_prefixes() -> Vec<String> {
+    let paths = paths();
+    let Ok(games) = read_games(&paths.database) else {
+        return Vec::new();
+    };
+
+    let home = home_dir();
+    let mut prefixes: Vec<String> = games
+        .iter()
+        .filter(|game| game.installed && game.runner == "wine")
+        .filter_map(|game| read_game_config(&paths.games_config_dir, &game.configpath)?.prefix)
+        .map(|prefix| match prefix.strip_prefix("~/") {
+            Some(rest) => home.join(rest).to_string_lossy().to_string(),
+            None => prefix,
+        })
+        .collect();
+    prefixes.sort();
+    prefixes.dedup();
+    prefixes
+}
+
+/// Looks `program` up on PATH, falling back to /usr/bin.
+fn find_program(program: &str) -> PathBuf {
+    env::var_os("PATH")
+        .and_then(|paths| env::split_paths(&paths).map(|dir| dir.join(program)).find(|p| p.is_file()))
+        .unwrap_or_else(|| Path::new("/usr/bin").join(program))
+}
+
+/// Executable and arguments that start game `id` through Lutris.
+pub fn launch_command(id: i64, flatpak: bool) -> (String, String) {
+    let uri = format!("lutris:rungameid/{}", id);
+    if flatpak {
+        let flatpak = find_program("flatpak");
+        (flatpak.to_string_lossy().to_string(), format!("run {} {}", FLATPAK_ID, uri))
+    } else {
+        (find_program("lutris").to_string_lossy().to_string(), uri)
+    }
+}
+
+pub fn scan() -> ScanResult {
+    scan_paths(&paths())
+}
+
+pub fn scan_paths(paths: &LutrisPaths) -> ScanResult {
+    let mut result = ScanResult::default();
+
+    let games = match read_games(&paths.database) {
+        Ok(games) => games,
+        Err(e) => {
+            println!("{}", e);
+            return result;
+        }
+    };
+
+    for game in games {
+        let id = game.id.to_string();
+        let name = if game.name.is_empty() { game.slug.clone() } else { game.name.clone() };
+
+        if !game.installed {
+            result.skip(LAUNCHER_ID, &id, &name, "Not installed");
+            continue;
+        }
+
+        let config = read_game_config(&paths.games_config_dir, &game.configpath).unwrap_or_default();
+        let install_path = [Some(game.directory.clone()), config.working_dir.clone(), config.prefix.clone()]
+            .into_iter()
+            .flatten()
+            .find(|dir| !dir.is_empty())
+            .or_else(|| {
+                let exe = config.exe.as_ref()?;
+                Path::new(exe).parent().map(|dir| dir.to_string_lossy().to_string())
+            })
+            .unwrap_or_default();
+
+        let (executable, arguments) = launch_command(game.id, paths.flatpak);
+        result.games.push(GameInfo {
+            id,
+            name,
+            executable,
+            install_path,
+            launcher_id: LAUNCHER_ID.to_string(),
+            launch_options: Some(arguments),
+            ..Default::default()
+        });
+    }
+
+    result
+}
+
+pub struct Lutris;
+
+impl Launcher for Lutris {
+    fn id(&self) -> &str {
+        LAUNCHER_ID
+    }
+
+    fn name(&self) -> &str {
+        "Lutris"
+    }
+
+    fn icon(&self) -> &str {
+        "🦦"
+    }
+
+    fn detect(&self) -> bool {
+        paths().database.exists()
+    }
+
+    fn install_path(&self) -> Option<String> {
+        paths().database.parent().map(|p| p.to_string_lossy().to_string())
+    }
+
+    fn scan_games(&self) -> ScanResult {
+        scan()
+    }
+
+    fn runs_in_wine(&self) -> bool {
+        false
+    }
+}
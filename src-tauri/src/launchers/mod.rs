@@ -0,0 +1,228 @@
+// Per-launcher game detection. Each launcher lives in its own module and
+// implements `Launcher`; `all()` lists them in the order they are shown. A
+// detector turns the launcher's own install records into `GameInfo` entries
+// and reports installs it had to leave out in `skipped`.
+
+pub mod amazon;
+pub mod battlenet;
+pub mod custom;
+pub mod ea;
+pub mod epic;
+pub mod gog;
+pub mod heroic;
+pub mod itch;
+pub mod lutris;
+pub mod riot;
+pub mod rockstar;
+pub mod steam;
+pub mod ubisoft;
+pub mod wine;
+
+mod protobuf;
+
+use serde::{Deserialize, Serialize};
+
+use epic::InstallConsistency;
+use crate::registry::{self, Hive};
+use crate::{GameInfo, LauncherInfo};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedGame {
+    pub id: String,
+    pub name: String,
+    pub launcher_id: String,
+    pub reason: String,
+    /// How the launcher's install records disagreed, when that is why the
+    /// game was skipped.
+    #[serde(default)]
+    pub install_consistency: Option<InstallConsistency>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScanResult {
+    pub games: Vec<GameInfo>,
+    pub skipped: Vec<SkippedGame>,
+}
+
+impl ScanResult {
+    pub fn skip(&mut self, launcher_id: &str, id: &str, name: &str, reason: impl Into<String>) {
+        self.skip_with_consistency(launcher_id, id, name, None, reason);
+    }
+
+    pub fn skip_with_consistency(
+        &mut self,
+        launcher_id: &str,
+        id: &str,
+        name: &str,
+        install_consistency: Option<InstallConsistency>,
+        reason: impl Into<String>,
+    ) {
+        let reason = reason.into();
+        println!("Skipping {} ({}): {}", name, launcher_id, reason);
+        self.skipped.push(SkippedGame {
+            id: id.to_string(),
+            name: name.to_string(),
+            launcher_id: launcher_id.to_string(),
+            reason,
+            install_consistency,
+        });
+    }
+}
+
+pub trait Launcher {
+    fn id(&self) -> &str;
+
+    fn name(&self) -> &str;
+
+    fn icon(&self) -> &str;
+
+    /// Whether the launcher (or something it installed) is present.
+    fn detect(&self) -> bool;
+
+    /// Where the launcher is installed, shown to the user once detected.
+    fn install_path(&self) -> Option<String>;
+
+    fn scan_games(&self) -> ScanResult;
+
+    /// Whether the launcher is a Windows program that can also be found
+    /// inside a Wine prefix.
+    fn runs_in_wine(&self) -> bool {
+        true
+    }
+
+    /// Detects the launcher and, if present, scans its games.
+    fn info(&self) -> LauncherInfo {
+        let detected = self.detect();
+        let scan = if detected { self.scan_games() } else { ScanResult::default() };
+        LauncherInfo {
+            id: self.id().to_string(),
+            name: self.name().to_string(),
+            icon: self.icon().to_string(),
+            detected,
+            games: scan.games,
+            install_path: if detected { self.install_path() } else { None },
+            skipped: scan.skipped,
+        }
+    }
+}
+
+/// Every launcher, in display order: the built-in ones, then the ones the
+/// user declared in `launchers.json`.
+pub fn all() -> Vec<Box<dyn Launcher>> {
+    let mut launchers: Vec<Box<dyn Launcher>> = vec![
+        Box::new(epic::Epic),
+        Box::new(steam::Steam),
+        Box::new(ubisoft::Ubisoft),
+        Box::new(ea::Ea),
+        Box::new(gog::Gog),
+        Box::new(battlenet::BattleNet),
+        Box::new(rockstar::Rockstar),
+        Box::new(amazon::Amazon),
+        Box::new(itch::Itch),
+        Box::new(riot::Riot),
+        Box::new(heroic::Heroic),
+        Box::new(lutris::Lutris),
+    ];
+
+    for launcher in custom::load() {
+        if launchers.iter().any(|l| l.id() == launcher.id) {
+            println!("Ignoring custom launcher {}: the id is already taken", launcher.id);
+            continue;
+        }
+        launchers.push(Box::new(launcher));
+    }
+    launchers
+}
+
+pub fn find(id: &str) -> Option<Box<dyn Launcher>> {
+    all().into_iter().find(|launcher| launcher.id() == id)
+}
+
+/// True if the key exists under HKEY_LOCAL_MACHINE.
+pub(crate) fn registry_key_exists(key_path: &str) -> bool {
+    registry::key_exists(Hive::LocalMachine, key_path)
+}
+
+/// True if a path (a Windows path inside a Wine prefix) exists.
+pub(crate) fn path_exists(path: &str) -> bool {
+    host_path(path).exists()
+}
+
+/// `%PROGRAMDATA%`, falling back to the default location.
+pub(crate) fn program_data() -> std::path::PathBuf {
+    if let Some(prefix) = windows_root() {
+        return prefix.program_data();
+    }
+    std::env::var_os("PROGRAMDATA")
+        .map(Into::into)
+        .unwrap_or_else(|| "C:\\ProgramData".into())
+}
+
+/// `%APPDATA%` (roaming) of the current user.
+pub(crate) fn app_data() -> std::path::PathBuf {
+    if let Some(prefix) = windows_root() {
+        return prefix.app_data();
+    }
+    std::env::var_os("APPDATA")
+        .map(Into::into)
+        .unwrap_or_else(|| {
+            let profile = std::env::var_os("USERPROFILE").unwrap_or_else(|| "C:\\Users\\Default".into());
+            std::path::Path::new(&profile).join("AppData").join("Roaming")
+        })
+}
+
+/// `%LOCALAPPDATA%` of the current user.
+pub(crate) fn local_app_data() -> std::path::PathBuf {
+    if let Some(prefix) = windows_root() {
+        return prefix.local_app_data();
+    }
+    std::env::var_os("LOCALAPPDATA")
+        .map(Into::into)
+        .unwrap_or_else(|| {
+            let profile = std::env::var_os("USERPROFILE").unwrap_or_else(|| "C:\\Users\\Default".into());
+            std::path::Path::new(&profile).join("AppData").join("Local")
+        })
+}
+
+/// `%USERPROFILE%`: the Windows profile folder inside the active Wine prefix
+/// or fixture root, otherwise the home folder.
+pub(crate) fn user_profile() -> std::path::PathBuf {
+    match windows_root() {
+        Some(prefix) => prefix.user_profile(),
+        None => home_dir(),
+    }
+}
+
+/// The current user's home folder.
+pub(crate) fn home_dir() -> std::path::PathBuf {
+    if let Some(root) = crate::sandbox::root() {
+        return root.join("home");
+    }
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(Into::into)
+        .unwrap_or_default()
+}
+
+/// Whether games found now can be started through their launcher's URI.
+/// Shortcuts for games in a Wine prefix always run the executable, since the
+/// URI handler only exists inside the prefix.
+pub(crate) fn uri_launch_available() -> bool {
+    wine::active_prefix().is_none()
+}
+
+/// The tree Windows paths resolve against: the active Wine prefix, then the
+/// fixture root. `None` means the real system.
+fn windows_root() -> Option<wine::WinePrefix> {
+    wine::active_prefix().or_else(|| crate::sandbox::root().map(wine::WinePrefix::new))
+}
+
+/// Where a path read from a launcher's records lives on this machine: inside
+/// the active Wine prefix or fixture root if there is one, otherwise the path
+/// itself.
+pub(crate) fn host_path(path: &str) -> std::path::PathBuf {
+    match windows_root() {
+        Some(prefix) => prefix.to_host_path(path),
+        None => path.into(),
+    }
+}
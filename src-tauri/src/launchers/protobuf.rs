@@ -0,0 +1,204 @@
+// Minimal protobuf wire-format reader for the launcher caches that store
+// protobuf messages without a published schema. Fields are returned in order
+// and callers pick the field numbers they know.
+
+pub(crate) enum FieldValue<'a> {
+    Varint(u64),
+    Fixed64(u64),
+    Bytes(&'a [u8]),
+    Fixed32(u32),
+}
+
+impl<'a> FieldValue<'a> {
+    pub(crate) fn as_u64(&self) -> Option<u64> {
+        match self {
+            FieldValue::Varint(v) | FieldValue::Fixed64(v) => Some(*v),
+            FieldValue::Fixed32(v) => Some(u64::from(*v)),
+            FieldValue::Bytes(_) => None,
+        }
+    }
+
+    pub(crate) fn as_bytes(&self) -> Option<&'a [u8]> {
+        match self {
+            FieldValue::Bytes(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_string(&self) -> Option<String> {
+        self.as_bytes().map(|b| String::from_utf8_lossy(b).into_owned())
+    }
+}
+
+/// Decodes every field of one message. Fails on malformed input rather than
+/// guessing, since a misread length would desynchronise everything after it.
+pub(crate) fn parse_message(data: &[u8]) -> Result<Vec<(u32, FieldValue<'_>)>, String> {
+    let mut fields = Vec::new();
+    let mut pos = 0;
+
+    while pos < data.len() {
+        let key = read_varint(data, &mut pos)?;
+        let number = u32::try_from(key >> 3).map_err(|_| format!("Invalid field number at offset {}", pos))?;
+        let value = match key & 0x7 {
+            0 => FieldValue::Varint(read_varint(data, &mut pos)?),
+            1 => FieldValue::Fixed64(u64::from_le_bytes(take(data, &mut pos, 8)?.try_into().unwrap())),
+            2 => {
+                let len = usize::try_from(read_varint(data, &mut pos)?)
+                    .map_err(|_| format!("Invalid length at offset {}", pos))?;
+                FieldValue::Bytes(take(data, &mut pos, len)?)
+            }
+            5 => FieldValue::Fixed32(u32::from_le_bytes(take(data, &mut pos, 4)?.try_into().unwrap())),
+            other => return Err(format!("Unsupported wire type {} at offset {}", other, pos)),
+        };
+        fields.push((number, value));
+    }
+
+    Ok(fields)
+}
+
+fn read_varint(data: &[u8], pos: &mut usize) -> Result<u64, String> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *data.get(*pos).ok_or_else(|| format!("Truncated varint at offset {}", pos))?;
+        *pos += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(format!("Varint too long at offset {}", pos))
+}
+
+fn take<'a>(data: &'a [u8], pos: &mut usize, len: usize) -> Result<&'a [u8], String> {
+    let end = pos.checked_add(len).filter(|&end| end <= data.len());
+    let end = end.ok_or_else(|| format!("Field runs past the end of data at offset {}", pos))?;
+    let bytes = &data[*pos..end];
+    *pos = end;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Encodes a message by hand, so decoder tests do not depend on a
+    /// protobuf library.
+    #[derive(Default)]
+    pub(crate) struct Message(Vec<u8>);
+
+    impl Message {
+        fn push_varint(&mut self, mut value: u64) {
+            while value >= 0x80 {
+                self.0.push((value as u8) | 0x80);
+                value >>= 7;
+            }
+            self.0.push(value as u8);
+        }
+
+        fn key(mut self, number: u32, wire_type: u8) -> Self {
+            self.push_varint((u64::from(number) << 3) | u64::from(wire_type));
+            self
+        }
+
+        pub(crate) fn varint(self, number: u32, value: u64) -> Self {
+            let mut m = self.key(number, 0);
+            m.push_varint(value);
+            m
+        }
+
+        pub(crate) fn bytes(self, number: u32, value: &[u8]) -> Self {
+            let mut m = self.key(number, 2);
+            m.push_varint(value.len() as u64);
+            m.0.extend_from_slice(value);
+            m
+        }
+
+        pub(crate) fn string(self, number: u32, value: &str) -> Self {
+            self.bytes(number, value.as_bytes())
+        }
+
+        pub(crate) fn message(self, number: u32, value: Message) -> Self {
+            self.bytes(number, &value.build())
+        }
+
+        pub(crate) fn build(self) -> Vec<u8> {
+            self.0
+        }
+    }
+
+    #[test]
+    fn decodes_varints() {
+        // The example from the protobuf encoding guide: field 1 = 150.
+        let fields = parse_message(&[0x08, 0x96, 0x01]).unwrap();
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0].0, 1);
+        assert_eq!(fields[0].1.as_u64(), Some(150));
+        assert_eq!(fields[0].1.as_bytes(), None);
+
+        let data = Message::default().varint(2, 0).varint(3, u64::MAX).varint(1000, 1).build();
+        let fields = parse_message(&data).unwrap();
+        let values: Vec<(u32, Option<u64>)> = fields.iter().map(|(n, v)| (*n, v.as_u64())).collect();
+        assert_eq!(values, [(2, Some(0)), (3, Some(u64::MAX)), (1000, Some(1))]);
+    }
+
+    #[test]
+    fn decodes_length_delimited_and_fixed_fields() {
+        let fields = parse_message(&[0x12, 0x07, b't', b'e', b's', b't', b'i', b'n', b'g']).unwrap();
+        assert_eq!(fields[0].0, 2);
+        assert_eq!(fields[0].1.as_string().as_deref(), Some("testing"));
+        assert_eq!(fields[0].1.as_u64(), None);
+
+        let mut data = Message::default().bytes(1, b"").build();
+        data.push((2 << 3) | 1);
+        data.extend_from_slice(&0x0102_0304_0506_0708u64.to_le_bytes());
+        data.push((3 << 3) | 5);
+        data.extend_from_slice(&0xdead_beefu32.to_le_bytes());
+        let fields = parse_message(&data).unwrap();
+        assert_eq!(fields[0].1.as_bytes(), Some(&[][..]));
+        assert_eq!(fields[1].1.as_u64(), Some(0x0102_0304_0506_0708));
+        assert_eq!(fields[2].1.as_u64(), Some(0xdead_beef));
+    }
+
+    #[test]
+    fn decodes_nested_messages() {
+        let data = Message::default()
+            .message(1, Message::default().string(1, "inner").message(2, Message::default().varint(1, 7)))
+            .varint(2, 9)
+            .build();
+        let outer = parse_message(&data).unwrap();
+        assert_eq!(outer.len(), 2);
+
+        let inner = parse_message(outer[0].1.as_bytes().unwrap()).unwrap();
+        assert_eq!(inner[0].1.as_string().as_deref(), Some("inner"));
+        let innermost = parse_message(inner[1].1.as_bytes().unwrap()).unwrap();
+        assert_eq!(innermost[0].1.as_u64(), Some(7));
+        assert_eq!(outer[1].1.as_u64(), Some(9));
+    }
+
+    #[test]
+    fn rejects_truncated_input() {
+        assert!(parse_message(&[]).unwrap().is_empty());
+        // Varint with its continuation bit set on the last byte.
+        assert!(parse_message(&[0x08, 0x96]).is_err());
+        // Key without a value.
+        assert!(parse_message(&[0x08]).is_err());
+        // Length running past the end.
+        assert!(parse_message(&[0x12, 0x05, b'a', b'b']).is_err());
+        // Fixed-size values cut short.
+        assert!(parse_message(&[0x09, 1, 2, 3, 4, 5, 6, 7]).is_err());
+        assert!(parse_message(&[0x0d, 1, 2, 3]).is_err());
+        // A complete message followed by half a field.
+        let mut data = Message::default().string(1, "ok").build();
+        data.extend_from_slice(&[0x12, 0x03, b'x']);
+        assert!(parse_message(&data).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        // Eleven continuation bytes never end a varint.
+        assert!(parse_message(&[0x08, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]).is_err());
+        // Groups (wire types 3 and 4) and unknown wire types are not supported.
+        assert!(parse_message(&[0x0b]).is_err());
+        assert!(parse_message(&[0x0f]).is_err());
+    }
+}
@@ -0,0 +1,197 @@
+// Riot Client games (League of Legends, VALORANT, Legends of Runeterra).
+//
+// %PROGRAMDATA%\Riot Games\RiotClientInstalls.json points at the Riot Client,
+// and every installed product has
+// Metadata\<product>.<patchline>\<product>.<patchline>.product_settings.yaml
+// with its install folder. Games are always started through
+// RiotClientServices.exe, which handles login and patching.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use super::{host_path, program_data, Launcher, ScanResult};
+use crate::GameInfo;
+
+const LAUNCHER_ID: &str = "riot";
+
+/// Metadata folders that describe the Riot Client itself.
+const NON_GAME_PRODUCTS: &[&str] = &["riot_client"];
+
+const KNOWN_PRODUCTS: &[(&str, &str)] = &[
+    ("league_of_legends", "League of Legends"),
+    ("valorant", "VALORANT"),
+    ("bacon", "Legends of Runeterra"),
+    ("lion", "2XKO"),
+];
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ClientInstalls {
+    pub rc_default: Option<String>,
+    pub rc_live: Option<String>,
+    pub associated_client: std::collections::HashMap<String, String>,
+}
+
+impl ClientInstalls {
+    /// The client that manages `install_path`, falling back to the default client.
+    pub fn client_for(&self, install_path: &str) -> Option<String> {
+        let normalize = |p: &str| p.replace('\\', "/").trim_end_matches('/').to_lowercase();
+        let wanted = normalize(install_path);
+        self.associated_client
+            .iter()
+            .find(|(dir, _)| normalize(dir) == wanted)
+            .map(|(_, client)| client.clone())
+            .or_else(|| self.rc_default.clone())
+            .or_else(|| self.rc_live.clone())
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ProductSettings {
+    pub product_install_full_path: String,
+    pub shortcut_name: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct RiotProduct {
+    pub product: String,
+    pub patchline: String,
+    pub settings: ProductSettings,
+}
+
+impl RiotProduct {
+    pub fn display_name(&self) -> String {
+        KNOWN_PRODUCTS
+            .iter()
+            .find(|(id, _)| *id == self.product)
+            .map(|(_, name)| name.to_string())
+            .or_else(|| {
+                let name = self.settings.shortcut_name.trim_end_matches(".lnk");
+                (!name.is_empty()).then(|| name.to_string())
+            })
+            .unwrap_or_else(|| self.product.clone())
+    }
+
+    pub fn launch_arguments(&self) -> String {
+        format!("--launch-product={} --launch-patchline={}", self.product, self.patchline)
+    }
+}
+
+pub fn riot_data_dir() -> PathBuf {
+    program_data().join("Riot Games")
+}
+
+pub fn read_client_installs(riot_dir: &Path) -> Result<ClientInstalls, String> {
+    let path = riot_dir.join("RiotClientInstalls.json");
+    let text = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&text).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+/// Every `Metadata\<product>.<patchline>` folder that has product settings.
+pub fn read_products(riot_dir: &Path) -> Vec<RiotProduct> {
+    let Ok(entries) = fs::read_dir(riot_dir.join("Metadata")) else {
+        return Vec::new();
+    };
+
+    let mut products: Vec<RiotProduct> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let folder = entry.file_name().into_string().ok()?;
+            let settings_path = entry.path().join(format!("{}.product_settings.yaml", folder));
+            let text = fs::read_to_string(&settings_path).ok()?;
+            let settings: ProductSettings = match serde_yaml::from_str(&text) {
+                Ok(settings) => settings,
+                Err(e) => {
+                    println!("Failed to parse {}: {}", settings_path.display(), e);
+                    return None;
+                }
+            };
+            let (product, patchline) = folder.split_once('.').unwrap_or((folder.as_str(), "live"));
+            Some(RiotProduct {
+                product: product.to_string(),
+                patchline: patchline.to_string(),
+                settings,
+            })
+        })
+        .collect();
+    products.sort_by(|a, b| a.product.cmp(&b.product).then(a.patchline.cmp(&b.patchline)));
+    products
+}
+
+pub fn scan() -> ScanResult {
+    let riot_dir = riot_data_dir();
+    let installs = read_client_installs(&riot_dir).unwrap_or_else(|e| {
+        println!("{}", e);
+        ClientInstalls::default()
+    });
+    scan_products(&installs, &read_products(&riot_dir))
+}
+
+pub fn scan_products(installs: &ClientInstalls, products: &[RiotProduct]) -> ScanResult {
+    let mut result = ScanResult::default();
+
+    for product in products {
+        if NON_GAME_PRODUCTS.contains(&product.product.as_str()) {
+            continue;
+        }
+
+        let id = format!("{}.{}", product.product, product.patchline);
+        let name = product.display_name();
+        let install_path = product.settings.product_install_full_path.replace('/', "\\");
+
+        if install_path.is_empty() || !host_path(&install_path).is_dir() {
+            result.skip(LAUNCHER_ID, &id, &name, format!("Install folder missing: {}", install_path));
+            continue;
+        }
+        let Some(client) = installs.client_for(&product.settings.product_install_full_path) else {
+            result.skip(LAUNCHER_ID, &id, &name, "Riot Client not found");
+            continue;
+        };
+        let client = client.replace('/', "\\");
+        let client_dir = client.rsplit_once('\\').map(|(dir, _)| dir.to_string());
+
+        result.games.push(GameInfo {
+            id,
+            name,
+            executable: client,
+            install_path,
+            launcher_id: LAUNCHER_ID.to_string(),
+            launch_options: Some(product.launch_arguments()),
+            working_dir: client_dir,
+            ..Default::default()
+        });
+    }
+
+    result
+}
+
+pub struct Riot;
+
+impl Launcher for Riot {
+    fn id(&self) -> &str {
+        LAUNCHER_ID
+    }
+
+    fn name(&self) -> &str {
+        "Riot Client"
+    }
+
+    fn icon(&self) -> &str {
+        "👊"
+    }
+
+    fn detect(&self) -> bool {
+        riot_data_dir().join("RiotClientInstalls.json").exists()
+    }
+
+    fn install_path(&self) -> Option<String> {
+        Some(riot_data_dir().to_string_lossy().to_string())
+    }
+
+    fn scan_games(&self) -> ScanResult {
+        scan()
+    }
+}
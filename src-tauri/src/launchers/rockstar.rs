@@ -0,0 +1,193 @@
+// Rockstar Games Launcher titles.
+//
+// Each installed title has a key under
+// HKLM\SOFTWARE\WOW6432Node\Rockstar Games\<Title> with its InstallFolder.
+// The launcher's titles.dat (JSON) lists the same installs by title id and is
+// used to pick up titles whose registry key is missing. Games are started
+// through the launcher with `-launchTitleInFolder "<install folder>"`, which
+// is what the launcher's own desktop shortcuts do.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value as Json;
+
+use super::{host_path, path_exists, program_data, registry_key_exists, Launcher, ScanResult};
+use crate::registry::{self, Hive};
+use crate::GameInfo;
+
+const LAUNCHER_ID: &str = "rockstar";
+const ROCKSTAR_KEY: &str = "SOFTWARE\\WOW6432Node\\Rockstar Games";
+const DEFAULT_LAUNCHER_DIR: &str = "C:\\Program Files\\Rockstar Games\\Launcher";
+
+/// Keys under `Rockstar Games` that belong to the launcher itself.
+const NON_GAME_KEYS: &[&str] = &["Launcher", "Rockstar Games Social Club", "Social Club"];
+
+/// title id -> (display name, main executable).
+const KNOWN_TITLES: &[(&str, &str, &str)] = &[
+    ("gta5", "Grand Theft Auto V", "GTA5.exe"),
+    ("rdr2", "Red Dead Redemption 2", "RDR2.exe"),
+    ("lanoire", "L.A. Noire", "LANoire.exe"),
+    ("lanoirevr", "L.A. Noire: The VR Case Files", "LANoireVR.exe"),
+    ("mp3", "Max Payne 3", "MaxPayne3.exe"),
+    ("gta4", "Grand Theft Auto IV", "GTAIV.exe"),
+    ("gtasa", "Grand Theft Auto: San Andreas", "gta_sa.exe"),
+    ("gtavc", "Grand Theft Auto: Vice City", "gta-vc.exe"),
+    ("gta3", "Grand Theft Auto III", "gta3.exe"),
+    ("bully", "Bully: Scholarship Edition", "Bully.exe"),
+    ("gta3unreal", "Grand Theft Auto III - The Definitive Edition", "Gameface\\Binaries\\Win64\\LibertyCity.exe"),
+    ("gtavcunreal", "Grand Theft Auto: Vice City - The Definitive Edition", "Gameface\\Binaries\\Win64\\ViceCity.exe"),
+    ("gtasaunreal", "Grand Theft Auto: San Andreas - The Definitive Edition", "Gameface\\Binaries\\Win64\\SanAndreas.exe"),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RockstarTitle {
+    pub title_id: Option<String>,
+    pub name: String,
+    pub install_folder: String,
+}
+
+pub fn launcher_dir() -> PathBuf {
+    registry::get_string(Hive::LocalMachine, &format!("{}\\Launcher", ROCKSTAR_KEY), "InstallFolder")
+        .map(|dir| host_path(&dir))
+        .unwrap_or_else(|| host_path(DEFAULT_LAUNCHER_DIR))
+}
+
+pub fn titles_dat_path() -> PathBuf {
+    program_data().join("Rockstar Games").join("Launcher").join("titles.dat")
+}
+
+pub fn read_registry_titles() -> Vec<RockstarTitle> {
+    registry::subkeys(Hive::LocalMachine, ROCKSTAR_KEY)
+        .into_iter()
+        .filter(|name| !NON_GAME_KEYS.iter().any(|skip| skip.eq_ignore_ascii_case(name)))
+        .filter_map(|name| {
+            let key = format!("{}\\{}", ROCKSTAR_KEY, name);
+            let install_folder = registry::get_string(Hive::LocalMachine, &key, "InstallFolder")?;
+            Some(RockstarTitle {
+                title_id: title_by_name(&name).map(|(id, ..)| id.to_string()),
+                name,
+                install_folder,
+            })
+        })
+        .collect()
+}
+
+/// Reads titles.dat. Entries are objects with a title id (`ti`) and install
+/// location (`il`); the long key names are accepted as well.
+pub fn parse_titles_dat(text: &str) -> Result<Vec<RockstarTitle>, String> {
+    let json: Json = serde_json::from_str(text.trim_start_matches('\u{feff}')).map_err(|e| e.to_string())?;
+    let list = match &json {
+        Json::Array(list) => list,
+        Json::Object(root) => match root.get("tl").or_else(|| root.get("titles")) {
+            Some(Json::Array(list)) => list,
+            _ => return Ok(Vec::new()),
+        },
+        _ => return Ok(Vec::new()),
+    };
+
+    let field = |entry: &Json, keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| entry.get(*key).and_then(Json::as_str))
+            .map(str::to_string)
+    };
+
+    Ok(list
+        .iter()
+        .filter_map(|entry| {
+            let title_id = field(entry, &["ti", "titleId", "id"])?;
+            let install_folder = field(entry, &["il", "installLocation", "installFolder"])?;
+            let name = title_by_id(&title_id)
+                .map(|(_, name, _)| name.to_string())
+                .unwrap_or_else(|| title_id.clone());
+            Some(RockstarTitle { title_id: Some(title_id), name, install_folder })
+        })
+        .collect())
+}
+
+fn title_by_id(title_id: &str) -> Option<&'static (&'static str, &'static str, &'static str)> {
+    KNOWN_TITLES.iter().find(|(id, ..)| id.eq_ignore_ascii_case(title_id))
+}
+
+fn title_by_name(name: &str) -> Option<&'static (&'static str, &'static str, &'static str)> {
+    let simplify = |s: &str| s.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_lowercase();
+    KNOWN_TITLES.iter().find(|(_, known, _)| simplify(known) == simplify(name))
+}
+
+pub fn scan() -> ScanResult {
+    let mut titles = read_registry_titles();
+    match fs::read_to_string(titles_dat_path()).map_err(|e| e.to_string()).and_then(|t| parse_titles_dat(&t)) {
+        Ok(dat_titles) => titles.extend(dat_titles),
+        Err(e) => println!("Failed to read Rockstar titles.dat: {}", e),
+    }
+    scan_titles(&launcher_dir(), &titles)
+}
+
+pub fn scan_titles(launcher_dir: &Path, titles: &[RockstarTitle]) -> ScanResult {
+    let mut result = ScanResult::default();
+    let mut seen: HashSet<String> = HashSet::new();
+    let launcher_exe = launcher_dir.join("Launcher.exe");
+
+    for title in titles {
+        let install_folder = title.install_folder.trim_end_matches(['\\', '/']).to_string();
+        // Registry and titles.dat usually describe the same install.
+        if !seen.insert(install_folder.to_lowercase()) {
+            continue;
+        }
+
+        let id = title.title_id.clone().unwrap_or_else(|| title.name.clone());
+        if !host_path(&install_folder).is_dir() {
+            result.skip(
+                LAUNCHER_ID,
+                &id,
+                &title.name,
+                format!("Install folder missing: {}", install_folder),
+            );
+            continue;
+        }
+
+        result.games.push(GameInfo {
+            id,
+            name: title.name.clone(),
+            executable: launcher_exe.to_string_lossy().to_string(),
+            install_path: install_folder.clone(),
+            launcher_id: LAUNCHER_ID.to_string(),
+            launch_options: Some(format!("-launchTitleInFolder \"{}\"", install_folder)),
+            working_dir: Some(launcher_dir.to_string_lossy().to_string()),
+            ..Default::default()
+        });
+    }
+
+    result
+}
+
+pub struct Rockstar;
+
+impl Launcher for Rockstar {
+    fn id(&self) -> &str {
+        LAUNCHER_ID
+    }
+
+    fn name(&self) -> &str {
+        "Rockstar Games Launcher"
+    }
+
+    fn icon(&self) -> &str {
+        "🎸"
+    }
+
+    fn detect(&self) -> bool {
+        registry_key_exists(&format!("{}\\Launcher", ROCKSTAR_KEY))
+            || path_exists("C:\\Program Files\\Rockstar Games\\Launcher")
+            || path_exists("C:\\Program Files (x86)\\Rockstar Games\\Launcher")
+    }
+
+    fn install_path(&self) -> Option<String> {
+        Some(launcher_dir().to_string_lossy().to_string())
+    }
+
+    fn scan_games(&self) -> ScanResult {
+        scan()
+    }
+}
@@ -0,0 +1,39 @@
+// Steam itself. It is listed so the user can see it was found, but its games
+// are never scanned: Steam is where games are imported to.
+
+use super::{Launcher, ScanResult};
+use crate::steam::install;
+
+const LAUNCHER_ID: &str = "steam";
+
+pub struct Steam;
+
+impl Launcher for Steam {
+    fn id(&self) -> &str {
+        LAUNCHER_ID
+    }
+
+    fn name(&self) -> &str {
+        "Steam"
+    }
+
+    fn icon(&self) -> &str {
+        "💨"
+    }
+
+    fn detect(&self) -> bool {
+        !install::installations().is_empty()
+    }
+
+    fn install_path(&self) -> Option<String> {
+        install::steam_path()
+    }
+
+    fn scan_games(&self) -> ScanResult {
+        ScanResult::default()
+    }
+
+    fn runs_in_wine(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,258 @@
+// Ubisoft Connect installs.
+//
+// Installed games are registered under
+// HKLM\SOFTWARE\WOW6432Node\Ubisoft\Launcher\Installs\<id>\InstallDir, but
+// titles and executables only exist in the launcher's
+// cache\configuration\configurations file. That file is a protobuf stream of
+// records, each holding the install id, a launch id and a YAML document with
+// the game's configuration. `uplay://` launches by install id, so the launch
+// id is not read.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_yaml::Value as Yaml;
+
+use super::protobuf::{self, FieldValue};
+use super::{host_path, path_exists, registry_key_exists, uri_launch_available, Launcher, ScanResult};
+use crate::registry::{self, Hive};
+use crate::GameInfo;
+
+const LAUNCHER_ID: &str = "ubisoft";
+const LAUNCHER_KEY: &str = "SOFTWARE\\WOW6432Node\\Ubisoft\\Launcher";
+const DEFAULT_LAUNCHER_DIR: &str = "C:\\Program Files (x86)\\Ubisoft\\Ubisoft Game Launcher";
+
+/// One record of the configurations cache.
+#[derive(Debug, Clone)]
+pub struct Configuration {
+    pub install_id: u64,
+    pub yaml: String,
+}
+
+/// The parts of a configuration YAML we need.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GameConfiguration {
+    pub name: Option<String>,
+    pub executable: Option<String>,
+    pub is_dlc: bool,
+}
+
+pub fn launcher_dir() -> PathBuf {
+    registry::get_string(Hive::LocalMachine, LAUNCHER_KEY, "InstallDir")
+        .map(|dir| host_path(&dir))
+        .unwrap_or_else(|| host_path(DEFAULT_LAUNCHER_DIR))
+}
+
+pub fn configurations_path(launcher_dir: &Path) -> PathBuf {
+    launcher_dir.join("cache").join("configuration").join("configurations")
+}
+
+/// install id -> install folder, from the registry.
+pub fn read_registry_installs() -> Vec<(String, String)> {
+    let installs = format!("{}\\Installs", LAUNCHER_KEY);
+    registry::subkeys(Hive::LocalMachine, &installs)
+        .into_iter()
+        .filter_map(|id| {
+            let dir = registry::get_string(Hive::LocalMachine, &format!("{}\\{}", installs, id), "InstallDir")?;
+            Some((id, dir))
+        })
+        .collect()
+}
+
+/// Splits the cache into records. Each top-level field 1 is one record with
+/// the install id (1) and YAML text (3).
+pub fn parse_configurations(data: &[u8]) -> Result<Vec<Configuration>, String> {
+    let mut records = Vec::new();
+
+    for (number, value) in protobuf::parse_message(data)? {
+        let (1, FieldValue::Bytes(record)) = (number, value) else {
+            continue;
+        };
+
+        let mut configuration = Configuration { install_id: 0, yaml: String::new() };
+        for (field, value) in protobuf::parse_message(record)? {
+            match field {
+                1 => configuration.install_id = value.as_u64().unwrap_or_default(),
+                3 => configuration.yaml = value.as_string().unwrap_or_default(),
+                _ => {}
+            }
+        }
+        records.push(configuration);
+    }
+
+    Ok(records)
+}
+
+/// Pulls the title and main executable out of a configuration YAML. Names are
+/// often localization keys ("l1") that resolve through `localizations.default`.
+pub fn parse_game_configuration(yaml: &str) -> Result<GameConfiguration, String> {
+    let doc: Yaml = serde_yaml::from_str(yaml).map_err(|e| e.to_string())?;
+    let root = &doc["root"];
+
+    let localize = |value: &Yaml| -> Option<String> {
+        let key = yaml_string(value)?;
+        Some(yaml_string(&doc["localizations"]["default"][key.as_str()]).unwrap_or(key))
+    };
+
+    let name = localize(&root["name"]).or_else(|| localize(&root["display_name"]));
+
+    let start_game = &root["start_game"];
+    let executable = ["offline", "online"].iter().find_map(|mode| {
+        let executables = start_game[*mode]["executables"].as_sequence()?;
+        executables
+            .iter()
+            .find_map(|exe| yaml_string(&exe["path"]["relative"]))
+    });
+
+    let is_dlc = matches!(&root["is_ulc"], Yaml::Bool(true))
+        || yaml_string(&root["is_ulc"]).is_some_and(|v| v.eq_ignore_ascii_case("yes"));
+
+    Ok(GameConfiguration { name, executable, is_dlc })
+}
+
+fn yaml_string(value: &Yaml) -> Option<String> {
+    match value {
+        Yaml::String(s) if !s.is_empty() => Some(s.clone()),
+        Yaml::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+pub fn launch_uri(install_id: &str) -> String {
+    format!("uplay://launch/{}/0", install_id)
+}
+
+pub fn scan() -> ScanResult {
+    let configurations = fs::read(configurations_path(&launcher_dir()))
+        .map_err(|e| e.to_string())
+        .and_then(|data| parse_configurations(&data));
+    let configurations = match configurations {
+        Ok(configurations) => configurations,
+        Err(e) => {
+            println!("Failed to read Ubisoft configurations cache: {}", e);
+            Vec::new()
+        }
+    };
+    scan_installs(&read_registry_installs(), &configurations)
+}
+
+pub fn scan_installs(installs: &[(String, String)], configurations: &[Configuration]) -> ScanResult {
+    let mut result = ScanResult::default();
+
+    let by_install_id: HashMap<String, &Configuration> = configurations
+        .iter()
+        .map(|c| (c.install_id.to_string(), c))
+        .collect();
+
+    for (install_id, install_dir) in installs {
+        let fallback_name = format!("Ubisoft {}", install_id);
+
+        let config = by_install_id
+            .get(install_id)
+            .map(|c| parse_game_configuration(&c.yaml))
+            .transpose()
+            .unwrap_or_else(|e| {
+                println!("Invalid configuration for Ubisoft game {}: {}", install_id, e);
+                None
+            })
+            .unwrap_or_default();
+        let name = config.name.clone().unwrap_or(fallback_name);
+
+        if config.is_dlc {
+            result.skip(LAUNCHER_ID, install_id, &name, "DLC");
+            continue;
+        }
+        if !host_path(install_dir).is_dir() {
+            result.skip(LAUNCHER_ID, install_id, &name, format!("Install folder missing: {}", install_dir));
+            continue;
+        }
+        let executable = config.executable.unwrap_or_default();
+        if executable.is_empty() && !uri_launch_available() {
+            result.skip(LAUNCHER_ID, install_id, &name, "No executable in the configurations cache");
+            continue;
+        }
+
+        result.games.push(GameInfo {
+            id: install_id.clone(),
+            name,
+            executable,
+            install_path: install_dir.trim_end_matches(['/', '\\']).to_string(),
+            launcher_id: LAUNCHER_ID.to_string(),
+            launch_uri: Some(launch_uri(install_id)),
+            ..Default::default()
+        });
+    }
+
+    result
+}
+
+pub struct Ubisoft;
+
+impl Launcher for Ubisoft {
+    fn id(&self) -> &str {
+        LAUNCHER_ID
+    }
+
+    fn name(&self) -> &str {
+        "Ubisoft Connect"
+    }
+
+    fn icon(&self) -> &str {
+        "🎯"
+    }
+
+    fn detect(&self) -> bool {
+        registry_key_exists(LAUNCHER_KEY)
+            || path_exists("C:\\Program Files (x86)\\Ubisoft\\Ubisoft Game Launcher")
+            || path_exists("C:\\Program Files\\Ubisoft\\Ubisoft Game Launcher")
+    }
+
+    fn install_path(&self) -> Option<String> {
+        Some(launcher_dir().to_string_lossy().to_string())
+    }
+
+    fn scan_games(&self) -> ScanResult {
+        scan()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::launchers::protobuf::tests::Message;
+
+    const YAML: &str = "root:\n  name: l1\n  start_game:\n    offline:\n      executables:\n        - path:\n            relative: Game.exe\nlocalizations:\n  default:\n    l1: Fixture Creed\n";
+
+    #[test]
+    fn splits_the_cache_into_records() {
+        let data = Message::default()
+            .message(1, Message::default().varint(1, 635).varint(2, 5059).string(3, YAML))
+            .varint(2, 1)
+            .message(1, Message::default().varint(1, 4311).varint(4, 99))
+            .build();
+
+        let records = parse_configurations(&data).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].install_id, 635);
+        assert_eq!(records[0].yaml, YAML);
+        assert_eq!(records[1].install_id, 4311);
+        assert!(records[1].yaml.is_empty());
+
+        let config = parse_game_configuration(&records[0].yaml).unwrap();
+        assert_eq!(config.name.as_deref(), Some("Fixture Creed"));
+        assert_eq!(config.executable.as_deref(), Some("Game.exe"));
+        assert!(!config.is_dlc);
+    }
+
+    #[test]
+    fn rejects_truncated_records() {
+        let data = Message::default().message(1, Message::default().varint(1, 635).string(3, YAML)).build();
+        assert!(parse_configurations(&data[..data.len() - 1]).is_err());
+
+        // The outer record is complete but the YAML length inside it is not.
+        let record = Message::default().varint(1, 635).string(3, YAML).build();
+        let data = Message::default().bytes(1, &record[..record.len() - 1]).build();
+        assert!(parse_configurations(&data).is_err());
+    }
+}
@@ -0,0 +1,359 @@
+// Windows launchers installed inside Wine prefixes.
+//
+// A prefix is a folder with the prefix's registry hives (`system.reg`,
+// `user.reg`) next to `drive_c`. While a prefix is active (see `with_prefix`)
+// the registry lookups of every detector go to its hives and the Windows
+// paths they find are resolved inside it, so the detectors themselves do not
+// need to know they are not running on Windows.
+
+use std::cell::RefCell;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use super::{home_dir, ScanResult};
+use crate::registry;
+use crate::steam::compatdata::{self, CompatData};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WinePrefix {
+    pub root: PathBuf,
+}
+
+impl WinePrefix {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn is_prefix(path: &Path) -> bool {
+        path.join("system.reg").is_file() && path.join("drive_c").is_dir()
+    }
+
+    pub fn drive_c(&self) -> PathBuf {
+        self.root.join("drive_c")
+    }
+
+    /// Maps a Windows path to its location on the host. `C:\` is `drive_c`;
+    /// other drive letters go through the `dosdevices` links Wine creates.
+    /// Components are matched case-insensitively, as Windows would. Paths that
+    /// are not Windows paths are returned unchanged.
+    pub fn to_host_path(&self, windows_path: &str) -> PathBuf {
+        let path = windows_path.strip_prefix("\\\\?\\").unwrap_or(windows_path);
+        let bytes = path.as_bytes();
+        if bytes.len() < 2 || bytes[1] != b':' || !bytes[0].is_ascii_alphabetic() {
+            return PathBuf::from(windows_path);
+        }
+
+        let letter = bytes[0].to_ascii_lowercase() as char;
+        let mut host = if letter == 'c' {
+            self.drive_c()
+        } else {
+            self.root.join("dosdevices").join(format!("{}:", letter))
+        };
+        for part in path[2..].split(['\\', '/']).filter(|p| !p.is_empty()) {
+            host = join_case_insensitive(&host, part);
+        }
+        host
+    }
+
+    /// Like `to_host_path`, but a relative path is taken to be relative to
+    /// the host folder `base` and joined under it case-insensitively.
+    pub fn to_host_path_in(&self, base: &Path, windows_path: &str) -> PathBuf {
+        let host = self.to_host_path(windows_path);
+        if windows_path.is_empty() || host.is_absolute() {
+            return host;
+        }
+        windows_path
+            .split(['\\', '/'])
+            .filter(|p| !p.is_empty() && *p != ".")
+            .fold(base.to_path_buf(), |dir, part| join_case_insensitive(&dir, part))
+    }
+
+    /// The Windows user profile inside the prefix: the one named after the
+    /// host user, Proton's `steamuser`, or the first non-public profile.
+    pub fn user_profile(&self) -> PathBuf {
+        let users = self.drive_c().join("users");
+        let mut names: Vec<String> = fs::read_dir(&users)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter(|e| e.path().is_dir())
+                    .map(|e| e.file_name().to_string_lossy().to_string())
+                    .filter(|name| !name.eq_ignore_ascii_case("Public"))
+                    .collect()
+            })
+            .unwrap_or_default();
+        names.sort();
+
+        let host_user = std::env::var("USER").unwrap_or_default();
+        let name = [host_user.as_str(), "steamuser"]
+            .into_iter()
+            .find(|wanted| names.iter().any(|n| n == wanted))
+            .map(str::to_string)
+            .or_else(|| names.into_iter().next())
+            .unwrap_or_else(|| "steamuser".to_string());
+        users.join(name)
+    }
+
+    pub fn program_data(&self) -> PathBuf {
+        self.to_host_path("C:\\ProgramData")
+    }
+
+    pub fn app_data(&self) -> PathBuf {
+        let profile = self.user_profile();
+        join_case_insensitive(&join_case_insensitive(&profile, "AppData"), "Roaming")
+    }
+
+    pub fn local_app_data(&self) -> PathBuf {
+        let profile = self.user_profile();
+        join_case_insensitive(&join_case_insensitive(&profile, "AppData"), "Local")
+    }
+}
+
+fn join_case_insensitive(dir: &Path, part: &str) -> PathBuf {
+    let exact = dir.join(part);
+    if exact.exists() {
+        return exact;
+    }
+    fs::read_dir(dir)
+        .ok()
+        .and_then(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.file_name())
+                .find(|name| name.to_string_lossy().eq_ignore_ascii_case(part))
+        })
+        .map(|name| dir.join(name))
+        .unwrap_or(exact)
+}
+
+thread_local! {
+    static ACTIVE: RefCell<Option<WinePrefix>> = RefCell::new(None);
+}
+
+/// The prefix set by an enclosing `with_prefix` on this thread.
+pub fn active_prefix() -> Option<WinePrefix> {
+    ACTIVE.with(|active| active.borrow().clone())
+}
+
+/// Runs `f` with registry lookups and Windows paths going to `prefix`.
+pub fn with_prefix<T>(prefix: &WinePrefix, f: impl FnOnce() -> T) -> Result<T, String> {
+    struct Restore(Option<WinePrefix>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            ACTIVE.with(|active| *active.borrow_mut() = previous);
+        }
+    }
+
+    let hives = registry::wine::load_prefix(&prefix.root)?;
+    let _restore = Restore(ACTIVE.with(|active| active.borrow_mut().replace(prefix.clone())));
+    Ok(registry::with_backend(Arc::new(hives), f))
+}
+
+/// Runs the Windows detectors inside `prefix`, or only the one for
+/// `launcher_id` if given. Paths in the results are host paths.
+pub fn scan_prefix(prefix: &WinePrefix, launcher_id: Option<&str>) -> Result<ScanResult, String> {
+    let mut result = with_prefix(prefix, || {
+        let mut result = ScanResult::default();
+        let wanted = |id: &str| launcher_id.is_none() || launcher_id == Some(id);
+        for launcher in super::all().iter().filter(|l| wanted(l.id()) && l.runs_in_wine() && l.detect()) {
+            let ScanResult { games, skipped } = launcher.scan_games();
+            result.games.extend(games);
+            result.skipped.extend(skipped);
+        }
+        result
+    })?;
+
+    let prefix_root = prefix.root.to_string_lossy().to_string();
+    for game in &mut result.games {
+        // Detectors leave executables and working folders relative to the
+        // install folder, with Windows separators.
+        let install_path = prefix.to_host_path(&game.install_path);
+        let host = |path: &str| prefix.to_host_path_in(&install_path, path).to_string_lossy().to_string();
+        game.wine_prefix = Some(prefix_root.clone());
+        game.install_path = install_path.to_string_lossy().to_string();
+        game.executable = host(&game.executable);
+        game.working_dir = game.working_dir.as_deref().map(host);
+        game.icon = game.icon.as_deref().map(host);
+    }
+    Ok(result)
+}
+
+/// Runs the Windows detectors (all, or only `launcher_id`) in the Proton
+/// prefixes of non-Steam shortcuts in the given Steam installations, tagging
+/// each game with the appid that owns the prefix. A Steam game's prefix only
+/// holds that game, so those are not scanned.
+pub fn scan_compat_prefixes(steam_paths: &[PathBuf], launcher_id: Option<&str>) -> ScanResult {
+    let mut result = ScanResult::default();
+    for steam_path in steam_paths {
+        for compat in compatdata::list(steam_path).into_iter().filter(CompatData::is_shortcut) {
+            let scan = match scan_prefix(&WinePrefix::new(&compat.prefix), launcher_id) {
+                Ok(scan) => scan,
+                Err(e) => {
+                    println!("Failed to scan Proton prefix {}: {}", compat.prefix.display(), e);
+                    continue;
+                }
+            };
+            for mut game in scan.games {
+                game.compat_app_id = Some(compat.app_id);
+                result.games.push(game);
+            }
+            result.skipped.extend(scan.skipped);
+        }
+    }
+    result
+}
+
+/// Prefixes worth looking at: `$WINEPREFIX`, `~/.wine`, every Bottles bottle
+/// and the prefixes of Lutris Wine games.
+pub fn discover_prefixes() -> Vec<WinePrefix> {
+    let home = home_dir();
+    let mut candidates: Vec<PathBuf> = Vec::new();
+
+    if let Some(prefix) = std::env::var_os("WINEPREFIX").filter(|_| crate::sandbox::root().is_none()) {
+        candidates.push(prefix.into());
+    }
+    candidates.push(home.join(".wine"));
+
+    for bottles in [
+        home.join(".local").join("share").join("bottles").join("bottles"),
+        home.join(".var")
+            .join("app")
+            .join("com.usebottles.bottles")
+            .join("data")
+            .join("bottles")
+            .join("bottles"),
+    ] {
+        if let Ok(entries) = fs::read_dir(&bottles) {
+            let mut bottles: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+            bottles.sort();
+            candidates.extend(bottles);
+        }
+    }
+
+    candidates.extend(super::lutris::wine_prefixes().into_iter().map(PathBuf::from));
+
+    let mut prefixes: Vec<WinePrefix> = Vec::new();
+    for candidate in candidates {
+        if !WinePrefix::is_prefix(&candidate) {
+            continue;
+        }
+        let canonical = fs::canonicalize(&candidate).unwrap_or_else(|_| candidate.clone());
+        if prefixes
+            .iter()
+            .all(|p| fs::canonicalize(&p.root).unwrap_or_else(|_| p.root.clone()) != canonical)
+        {
+            prefixes.push(WinePrefix::new(candidate));
+        }
+    }
+    prefixes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn prefix(name: &str) -> (TempDir, WinePrefix) {
+        let root = TempDir::new(name);
+        fs::create_dir_all(root.join("drive_c").join("Program Files (x86)").join("Steam")).unwrap();
+        let prefix = WinePrefix::new(root.path());
+        (root, prefix)
+    }
+
+    #[test]
+    fn maps_drive_c_case_insensitively() {
+        let (_root, prefix) = prefix("drive-c");
+        assert_eq!(
+            prefix.to_host_path("c:\\PROGRAM FILES (X86)\\steam\\steam.exe"),
+            prefix.drive_c().join("Program Files (x86)").join("Steam").join("steam.exe")
+        );
+        assert_eq!(
+            prefix.to_host_path("C:/Program Files (x86)/Steam/"),
+            prefix.drive_c().join("Program Files (x86)").join("Steam")
+        );
+        assert_eq!(prefix.to_host_path("\\\\?\\C:\\Games"), prefix.drive_c().join("Games"));
+    }
+
+    #[test]
+    fn maps_other_drives_through_dosdevices() {
+        let (_root, prefix) = prefix("dosdevices");
+        assert_eq!(
+            prefix.to_host_path("Z:\\home\\deck\\Games"),
+            prefix.root.join("dosdevices").join("z:").join("home").join("deck").join("Games")
+        );
+        assert_eq!(prefix.to_host_path("d:\\Games"), prefix.root.join("dosdevices").join("d:").join("Games"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn follows_the_z_drive_link() {
+        let (_root, prefix) = prefix("z-drive");
+        let host = prefix.root.join("host");
+        fs::create_dir_all(host.join("Games")).unwrap();
+        fs::create_dir_all(prefix.root.join("dosdevices")).unwrap();
+        std::os::unix::fs::symlink(&host, prefix.root.join("dosdevices").join("z:")).unwrap();
+
+        assert!(prefix.to_host_path("Z:\\games").is_dir());
+    }
+
+    #[test]
+    fn leaves_other_paths_alone() {
+        let (_root, prefix) = prefix("relative");
+        assert_eq!(prefix.to_host_path("Binaries\\Game.exe"), PathBuf::from("Binaries\\Game.exe"));
+        assert_eq!(prefix.to_host_path("/usr/bin/lutris"), PathBuf::from("/usr/bin/lutris"));
+        assert_eq!(prefix.to_host_path(""), PathBuf::from(""));
+    }
+
+    /// Sets `prefix` up with the EA app and one game whose executable is in
+    /// a subfolder, and returns the game's install folder.
+    fn ea_game(prefix: &WinePrefix) -> PathBuf {
+        fs::write(prefix.root.join("system.reg"), "WINE REGISTRY Version 2\n").unwrap();
+        let drive_c = prefix.drive_c();
+        fs::create_dir_all(drive_c.join("Program Files").join("Electronic Arts").join("EA Desktop")).unwrap();
+        let install = drive_c.join("Program Files").join("EA Games").join("Fixture Racer");
+        fs::create_dir_all(install.join("__Installer")).unwrap();
+        fs::create_dir_all(install.join("Bin").join("x64")).unwrap();
+        fs::write(install.join("Bin").join("x64").join("Racer.exe"), "").unwrap();
+        fs::write(
+            install.join("__Installer").join("installerdata.xml"),
+            "<DiPManifest><contentIDs><contentID>Origin.OFR.50.0001</contentID></contentIDs>\
+             <gameTitles><gameTitle locale=\"en_US\">Fixture Racer</gameTitle></gameTitles>\
+             <runtime><launcher><filePath>[HKEY_LOCAL_MACHINE\\SOFTWARE\\EA Games\\Fixture Racer\\Install Dir]\\bin\\X64\\racer.exe</filePath></launcher></runtime>\
+             </DiPManifest>",
+        )
+        .unwrap();
+        install
+    }
+
+    #[test]
+    fn resolves_relative_executables_under_the_install_folder() {
+        let (_root, prefix) = prefix("relative-exe");
+        let install = ea_game(&prefix);
+
+        let result = scan_prefix(&prefix, Some("ea")).unwrap();
+        assert_eq!(result.games.len(), 1, "{:?}", result.skipped);
+        let exe = install.join("Bin").join("x64").join("Racer.exe");
+        assert_eq!(result.games[0].executable, exe.to_string_lossy());
+
+        let shortcut = crate::shortcut_for_game(&result.games[0]);
+        assert_eq!(shortcut.exe(), format!("\"{}\"", exe.display()));
+        assert_eq!(shortcut.start_dir(), format!("\"{}\"", install.display()));
+    }
+
+    #[test]
+    fn only_scans_the_prefixes_of_shortcuts() {
+        let steam = TempDir::new("compatdata");
+        let compat_root = steam.join("steamapps").join("compatdata");
+        for app_id in ["1091500", "3000000001"] {
+            let prefix = WinePrefix::new(compat_root.join(app_id).join("pfx"));
+            fs::create_dir_all(prefix.drive_c()).unwrap();
+            ea_game(&prefix);
+        }
+
+        let result = scan_compat_prefixes(&[steam.to_path_buf()], Some("ea"));
+        let app_ids: Vec<Option<u32>> = result.games.iter().map(|g| g.compat_app_id).collect();
+        assert_eq!(app_ids, [Some(3000000001)]);
+    }
+}
@@ -2,36 +2,107 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use serde::{Deserialize, Serialize};
-use std::path::Path;
-use winreg::enums::*;
-use winreg::RegKey;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub mod launchers;
+pub mod registry;
+pub mod sandbox;
+pub mod steam;
+#[cfg(test)]
+mod test_support;
+
+use launchers::epic::InstallConsistency;
+use launchers::wine::{self, WinePrefix};
+use launchers::{ScanResult, SkippedGame};
+use steam::appid::ShortcutIds;
+use steam::compat_tools::{self, CompatTool};
+use steam::install::{self, SteamInstallation};
+use steam::shortcuts::{shortcuts_path, Shortcut, ShortcutsFile};
+use steam::users::{self, SteamUser};
 
 #[derive(Debug, Serialize, Deserialize)]
-struct LauncherInfo {
-    id: String,
-    name: String,
-    icon: String,
-    detected: bool,
-    games: Vec<GameInfo>,
-    install_path: Option<String>,
+pub struct LauncherInfo {
+    pub id: String,
+    pub name: String,
+    pub icon: String,
+    pub detected: bool,
+    pub games: Vec<GameInfo>,
+    pub install_path: Option<String>,
+    /// Installs that were found but will not be imported, with the reason.
+    #[serde(default)]
+    pub skipped: Vec<SkippedGame>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GameInfo {
+    pub id: String,
+    pub name: String,
+    pub executable: String,
+    pub install_path: String,
+    pub launcher_id: String,
+    pub icon: Option<String>,
+    /// Launcher URI to start the game through its launcher instead of running
+    /// the executable directly.
+    #[serde(default)]
+    pub launch_uri: Option<String>,
+    /// Whether the launcher's install records agree about this game (Epic only).
+    #[serde(default)]
+    pub install_consistency: Option<InstallConsistency>,
+    /// Arguments passed to the executable.
+    #[serde(default)]
+    pub launch_options: Option<String>,
+    /// Working directory, when it differs from `install_path`.
+    #[serde(default)]
+    pub working_dir: Option<String>,
+    /// Wine prefix the game was found in, for Windows launchers on Linux.
+    #[serde(default)]
+    pub wine_prefix: Option<String>,
+    /// Appid of the Steam shortcut whose Proton prefix (`compatdata/<appid>`)
+    /// holds the game.
+    #[serde(default)]
+    pub compat_app_id: Option<u32>,
+}
+
+/// Files games found inside Wine prefixes under the launcher they belong to,
+/// marking that launcher as detected.
+pub fn add_prefix_games(launchers: &mut [LauncherInfo], scan: ScanResult) {
+    for game in scan.games {
+        if let Some(launcher) = launchers.iter_mut().find(|l| l.id == game.launcher_id) {
+            launcher.detected = true;
+            launcher.games.push(game);
+        }
+    }
+    for skipped in scan.skipped {
+        if let Some(launcher) = launchers.iter_mut().find(|l| l.id == skipped.launcher_id) {
+            launcher.skipped.push(skipped);
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-struct GameInfo {
-    id: String,
-    name: String,
-    executable: String,
-    install_path: String,
-    launcher_id: String,
-    icon: Option<String>,
+pub struct ImportResult {
+    /// Games written for at least one user.
+    pub success: u32,
+    /// "Name (user <id>)" for every game a user's shortcuts could not be
+    /// written for.
+    pub failed: Vec<String>,
+    pub imported: Vec<ImportedGame>,
+    pub warnings: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-struct ImportResult {
-    success: u32,
-    failed: Vec<String>,
+pub struct ImportedGame {
+    pub game_id: String,
+    pub name: String,
+    pub launcher_id: String,
+    /// Steam user whose shortcuts.vdf holds the entry.
+    pub user_id: String,
+    pub shortcut: ShortcutIds,
 }
 
+// Learn more about Tauri commands at https://tauri.app/v1/guides/features/command
 #[tauri::command]
 fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
@@ -39,240 +110,267 @@ fn greet(name: &str) -> String {
 
 #[tauri::command]
 async fn detect_launchers() -> Result<Vec<LauncherInfo>, String> {
-    println!("Starting launcher detection...");
-
-    let mut launchers = Vec::new();
-
-    // Epic Games
-    let epic_detected = check_registry_key("SOFTWARE\\WOW6432Node\\Epic Games\\EpicGamesLauncher")
-        || check_path("C:\\Program Files (x86)\\Epic Games\\Launcher")
-        || check_path("C:\\Program Files\\Epic Games\\Launcher");
-
-    launchers.push(LauncherInfo {
-        id: "epic".to_string(),
-        name: "Epic Games".to_string(),
-        icon: "🎮".to_string(),
-        detected: epic_detected,
-        games: if epic_detected { get_epic_games().await } else { Vec::new() },
-        install_path: if epic_detected { Some("C:\\Program Files (x86)\\Epic Games\\Launcher".to_string()) } else { None },
-    });
-
-    // Ubisoft Connect
-    let ubisoft_detected = check_registry_key("SOFTWARE\\WOW6432Node\\Ubisoft\\Launcher")
-        || check_path("C:\\Program Files (x86)\\Ubisoft\\Ubisoft Game Launcher")
-        || check_path("C:\\Program Files\\Ubisoft\\Ubisoft Game Launcher");
-
-    launchers.push(LauncherInfo {
-        id: "ubisoft".to_string(),
-        name: "Ubisoft Connect".to_string(),
-        icon: "🎯".to_string(),
-        detected: ubisoft_detected,
-        games: if ubisoft_detected { get_ubisoft_games().await } else { Vec::new() },
-        install_path: if ubisoft_detected { Some("C:\\Program Files (x86)\\Ubisoft\\Ubisoft Game Launcher".to_string()) } else { None },
-    });
+    Ok(detect_all())
+}
 
-    // EA App
-    let ea_detected = check_registry_key("SOFTWARE\\WOW6432Node\\Electronic Arts\\EA Desktop")
-        || check_path("C:\\Program Files\\Electronic Arts\\EA Desktop")
-        || check_path("C:\\Program Files (x86)\\Electronic Arts\\EA Desktop");
-
-    launchers.push(LauncherInfo {
-        id: "ea".to_string(),
-        name: "EA App".to_string(),
-        icon: "⚡".to_string(),
-        detected: ea_detected,
-        games: if ea_detected { get_ea_games().await } else { Vec::new() },
-        install_path: if ea_detected { Some("C:\\Program Files\\Electronic Arts\\EA Desktop".to_string()) } else { None },
-    });
+/// Every launcher with its games, including the ones set up inside Proton
+/// prefixes.
+pub fn detect_all() -> Vec<LauncherInfo> {
+    println!("Starting launcher detection...");
 
-    // GOG Galaxy
-    let gog_detected = check_registry_key("SOFTWARE\\WOW6432Node\\GOG.com\\GalaxyClient")
-        || check_path("C:\\Program Files (x86)\\GOG Galaxy")
-        || check_path("C:\\Program Files\\GOG Galaxy");
-
-    launchers.push(LauncherInfo {
-        id: "gog".to_string(),
-        name: "GOG Galaxy".to_string(),
-        icon: "🌟".to_string(),
-        detected: gog_detected,
-        games: if gog_detected { get_gog_games().await } else { Vec::new() },
-        install_path: if gog_detected { Some("C:\\Program Files (x86)\\GOG Galaxy".to_string()) } else { None },
-    });
+    let mut launchers: Vec<LauncherInfo> = launchers::all().iter().map(|launcher| launcher.info()).collect();
 
-    // Battle.net
-    let battlenet_detected = check_registry_key("SOFTWARE\\WOW6432Node\\Blizzard Entertainment\\Battle.net")
-        || check_path("C:\\Program Files (x86)\\Battle.net")
-        || check_path("C:\\Program Files\\Battle.net");
-
-    launchers.push(LauncherInfo {
-        id: "battlenet".to_string(),
-        name: "Battle.net".to_string(),
-        icon: "⚔️".to_string(),
-        detected: battlenet_detected,
-        games: if battlenet_detected { get_battlenet_games().await } else { Vec::new() },
-        install_path: if battlenet_detected { Some("C:\\Program Files (x86)\\Battle.net".to_string()) } else { None },
-    });
+    // Windows launchers set up inside Proton prefixes, e.g. the EA app added
+    // as a non-Steam game on a Steam Deck
+    add_prefix_games(&mut launchers, compat_prefix_games(None));
 
     println!("Launcher detection completed. Found {} launchers", launchers.len());
-    Ok(launchers)
+    launchers
 }
 
 #[tauri::command]
 async fn get_games_by_launcher(launcher_id: String) -> Result<Vec<GameInfo>, String> {
     println!("Getting games for launcher: {}", launcher_id);
 
-    let games = match launcher_id.as_str() {
-        "epic" => get_epic_games().await,
-        "ubisoft" => get_ubisoft_games().await,
-        "ea" => get_ea_games().await,
-        "gog" => get_gog_games().await,
-        "battlenet" => get_battlenet_games().await,
-        _ => Vec::new(),
+    let mut games = match launchers::find(&launcher_id) {
+        Some(launcher) => launcher.scan_games().games,
+        None => Vec::new(),
     };
+    games.extend(compat_prefix_games(Some(&launcher_id)).games);
 
     println!("Found {} games for {}", games.len(), launcher_id);
     Ok(games)
 }
 
 #[tauri::command]
-async fn add_games_to_steam(games: Vec<GameInfo>) -> Result<ImportResult, String> {
-    println!("Adding {} games to Steam", games.len());
+async fn add_games_to_steam(
+    games: Vec<GameInfo>,
+    user_id: String,
+    steam_path: Option<String>,
+    compat_tool: Option<String>,
+) -> Result<ImportResult, String> {
+    import_games(&games, &user_id, steam_path, compat_tool)
+}
 
-    let mut success = 0;
-    let mut failed = Vec::new();
+/// Adds `games` as non-Steam shortcuts for `user_id` (or every user, see
+/// `users::resolve_target`), optionally assigning `compat_tool` to the
+/// Windows ones.
+pub fn import_games(
+    games: &[GameInfo],
+    user_id: &str,
+    steam_path: Option<String>,
+    compat_tool: Option<String>,
+) -> Result<ImportResult, String> {
+    println!("Adding {} games to Steam for user {}", games.len(), user_id);
+
+    let steam_path = steam_path.or_else(install::steam_path).ok_or("Steam installation not found")?;
+    let steam_path = PathBuf::from(steam_path);
+
+    let user_ids = users::resolve_target(&steam_path, user_id)?;
+
+    // Results are kept per user: one user's unreadable or unwritable
+    // shortcuts.vdf must not fail the games written for the others.
+    let mut imported: Vec<ImportedGame> = Vec::new();
+    let mut failed: Vec<String> = Vec::new();
+    let mut warnings = Vec::new();
+    let mut windows_app_ids: Vec<u32> = Vec::new();
+
+    for user_id in &user_ids {
+        let path = shortcuts_path(&steam_path, user_id);
+        let mut file = match ShortcutsFile::load(&path) {
+            Ok(file) => file,
+            Err(e) => {
+                println!("Skipping user {}: {}", user_id, e);
+                warnings.push(format!("Steam user {}: {}", user_id, e));
+                failed.extend(games.iter().map(|g| format!("{} (user {})", g.name, user_id)));
+                continue;
+            }
+        };
+
+        let mut user_imported = Vec::new();
+        let mut user_windows_app_ids = Vec::new();
+        for game in games {
+            let shortcut = shortcut_for_game(game);
+            let is_windows_exe = shortcut.exe().trim_matches('"').to_lowercase().ends_with(".exe");
+            let (app_id, added) = file.add(shortcut);
+            if is_windows_exe {
+                user_windows_app_ids.push(app_id);
+            }
+            if added {
+                println!("Added {} to Steam user {} (appid {})", game.name, user_id, app_id);
+            } else {
+                println!("{} is already in Steam user {}", game.name, user_id);
+            }
+            user_imported.push(ImportedGame {
+                game_id: game.id.clone(),
+                name: game.name.clone(),
+                launcher_id: game.launcher_id.clone(),
+                user_id: user_id.clone(),
+                shortcut: ShortcutIds::new(app_id),
+            });
+        }
 
-    for game in games {
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        if let Err(e) = file.save(&path) {
+            println!("Failed to save shortcuts for user {}: {}", user_id, e);
+            warnings.push(format!("Steam user {}: {}", user_id, e));
+            failed.extend(games.iter().map(|g| format!("{} (user {})", g.name, user_id)));
+            continue;
+        }
+        imported.extend(user_imported);
+        for app_id in user_windows_app_ids {
+            if !windows_app_ids.contains(&app_id) {
+                windows_app_ids.push(app_id);
+            }
+        }
+    }
 
-        if game.name.contains("Error") {
-            failed.push(game.name);
-        } else {
-            success += 1;
-            println!("Added {} to Steam", game.name);
+    // Games are told apart by launcher and id: two launchers may well have a
+    // game with the same title.
+    let success = imported
+        .iter()
+        .map(|g| (g.launcher_id.as_str(), g.game_id.as_str()))
+        .collect::<HashSet<_>>()
+        .len() as u32;
+
+    // Windows executables need a compatibility tool to start on Linux
+    if let Some(tool) = compat_tool.filter(|tool| !tool.is_empty()) {
+        if !windows_app_ids.is_empty() {
+            match compat_tools::set_tool(&steam_path, &windows_app_ids, &tool) {
+                Ok(()) => println!("Set compatibility tool {} for {} shortcuts", tool, windows_app_ids.len()),
+                Err(e) => warnings.push(format!("Failed to set compatibility tool {}: {}", tool, e)),
+            }
         }
     }
 
     println!("Steam import completed: {} success, {} failed", success, failed.len());
-    Ok(ImportResult { success, failed })
+    Ok(ImportResult { success, failed, imported, warnings })
 }
 
 #[tauri::command]
 fn check_steam_path() -> Option<String> {
-    get_steam_path()
+    install::steam_path()
 }
 
-fn check_registry_key(key_path: &str) -> bool {
-    let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
-    hklm.open_subkey(key_path).is_ok()
+#[tauri::command]
+fn list_steam_users(steam_path: Option<String>) -> Result<Vec<SteamUser>, String> {
+    let steam_path = steam_path.or_else(install::steam_path).ok_or("Steam installation not found")?;
+    Ok(users::list_users(Path::new(&steam_path)))
 }
 
-fn check_path(path: &str) -> bool {
-    Path::new(path).exists()
+#[tauri::command]
+fn list_steam_installations() -> Vec<SteamInstallation> {
+    install::installations()
 }
 
-fn get_steam_path() -> Option<String> {
-    if let Ok(hklm) = RegKey::predef(HKEY_LOCAL_MACHINE).open_subkey("SOFTWARE\\WOW6432Node\\Valve\\Steam") {
-        if let Ok(install_path) = hklm.get_value::<String, _>("InstallPath") {
-            return Some(install_path);
-        }
-    }
+#[tauri::command]
+fn list_compat_tools(steam_path: Option<String>) -> Result<Vec<CompatTool>, String> {
+    let steam_path = steam_path.or_else(install::steam_path).ok_or("Steam installation not found")?;
+    Ok(compat_tools::list_tools(Path::new(&steam_path)))
+}
 
-    let common_paths = [
-        "C:\\Program Files (x86)\\Steam",
-        "C:\\Program Files\\Steam",
-    ];
+#[tauri::command]
+fn list_wine_prefixes() -> Vec<String> {
+    wine::discover_prefixes()
+        .into_iter()
+        .map(|prefix| prefix.root.to_string_lossy().to_string())
+        .collect()
+}
 
-    for path in &common_paths {
-        if Path::new(path).exists() {
-            return Some(path.to_string());
-        }
+#[tauri::command]
+fn scan_wine_prefix(prefix: String) -> Result<ScanResult, String> {
+    let prefix = WinePrefix::new(prefix);
+    if !WinePrefix::is_prefix(&prefix.root) {
+        return Err(format!("Not a Wine prefix: {}", prefix.root.display()));
     }
-
-    None
+    wine::scan_prefix(&prefix, None)
 }
 
-async fn get_epic_games() -> Vec<GameInfo> {
-    vec![
-        GameInfo {
-            id: "fortnite".to_string(),
-            name: "Fortnite".to_string(),
-            executable: "FortniteClient-Win64-Shipping.exe".to_string(),
-            install_path: "C:\\Program Files\\Epic Games\\Fortnite".to_string(),
-            launcher_id: "epic".to_string(),
-            icon: None,
-        },
-        GameInfo {
-            id: "gta5-epic".to_string(),
-            name: "Grand Theft Auto V".to_string(),
-            executable: "GTA5.exe".to_string(),
-            install_path: "C:\\Program Files\\Epic Games\\GTAV".to_string(),
-            launcher_id: "epic".to_string(),
-            icon: None,
-        },
-    ]
-}
+/// The last scan of every Proton prefix and the Steam installations it
+/// covered, so listing one launcher's games after `detect_all` does not run
+/// the detectors in every prefix again.
+static COMPAT_PREFIX_SCAN: Mutex<Option<(Vec<PathBuf>, ScanResult)>> = Mutex::new(None);
+
+/// Games in Proton prefixes, from every launcher or only from `launcher_id`.
+/// A scan of every launcher refreshes `COMPAT_PREFIX_SCAN`; a single
+/// launcher is answered from it when it covers the same installations.
+fn compat_prefix_games(launcher_id: Option<&str>) -> ScanResult {
+    let steam_roots: Vec<PathBuf> = install::installations().into_iter().map(|install| PathBuf::from(install.root)).collect();
+
+    let Some(launcher_id) = launcher_id else {
+        let scan = wine::scan_compat_prefixes(&steam_roots, None);
+        *COMPAT_PREFIX_SCAN.lock().unwrap_or_else(|e| e.into_inner()) = Some((steam_roots, scan.clone()));
+        return scan;
+    };
 
-async fn get_ubisoft_games() -> Vec<GameInfo> {
-    vec![
-        GameInfo {
-            id: "ac-valhalla".to_string(),
-            name: "Assassin's Creed Valhalla".to_string(),
-            executable: "ACValhalla.exe".to_string(),
-            install_path: "C:\\Program Files\\Ubisoft\\Assassins Creed Valhalla".to_string(),
-            launcher_id: "ubisoft".to_string(),
-            icon: None,
-        },
-    ]
+    let cached = COMPAT_PREFIX_SCAN.lock().unwrap_or_else(|e| e.into_inner()).as_ref().and_then(|(roots, scan)| {
+        (*roots == steam_roots).then(|| ScanResult {
+            games: scan.games.iter().filter(|g| g.launcher_id == launcher_id).cloned().collect(),
+            skipped: scan.skipped.iter().filter(|g| g.launcher_id == launcher_id).cloned().collect(),
+        })
+    });
+    cached.unwrap_or_else(|| wine::scan_compat_prefixes(&steam_roots, Some(launcher_id)))
 }
 
-async fn get_ea_games() -> Vec<GameInfo> {
-    vec![
-        GameInfo {
-            id: "apex".to_string(),
-            name: "Apex Legends".to_string(),
-            executable: "r5apex.exe".to_string(),
-            install_path: "C:\\Program Files\\EA Games\\Apex".to_string(),
-            launcher_id: "ea".to_string(),
-            icon: None,
-        },
-    ]
-}
+fn shortcut_for_game(game: &GameInfo) -> Shortcut {
+    // Games that need their launcher running are started through its URI;
+    // everything else runs the executable directly. Steam on Linux cannot
+    // open a URI as a shortcut target, so hand it to xdg-open there. Games
+    // found in a Wine prefix always run their executable: the URI handler
+    // lives inside the prefix, and the game starts its launcher itself.
+    let launch_uri = game.launch_uri.as_ref().filter(|_| game.wine_prefix.is_none());
+    let (exe, mut launch_options) = match launch_uri {
+        Some(uri) if cfg!(target_os = "linux") => ("xdg-open".to_string(), uri.clone()),
+        Some(uri) => (uri.clone(), String::new()),
+        None => (
+            Path::new(&game.install_path).join(&game.executable).to_string_lossy().to_string(),
+            game.launch_options.clone().unwrap_or_default(),
+        ),
+    };
 
-async fn get_gog_games() -> Vec<GameInfo> {
-    vec![
-        GameInfo {
-            id: "witcher3-gog".to_string(),
-            name: "The Witcher 3: Wild Hunt".to_string(),
-            executable: "witcher3.exe".to_string(),
-            install_path: "C:\\GOG Games\\The Witcher 3 Wild Hunt".to_string(),
-            launcher_id: "gog".to_string(),
-            icon: None,
-        },
-    ]
-}
+    // Reuse the Proton prefix the game is installed in instead of letting
+    // Steam create an empty one for the new shortcut.
+    if let (Some(_), Some(prefix)) = (game.compat_app_id, &game.wine_prefix) {
+        if let Some(compat_data) = Path::new(prefix).parent() {
+            launch_options = format!(
+                "STEAM_COMPAT_DATA_PATH=\"{}\" %command% {}",
+                compat_data.display(),
+                launch_options
+            )
+            .trim_end()
+            .to_string();
+        }
+    }
 
-async fn get_battlenet_games() -> Vec<GameInfo> {
-    vec![
-        GameInfo {
-            id: "diablo4".to_string(),
-            name: "Diablo IV".to_string(),
-            executable: "Diablo IV.exe".to_string(),
-            install_path: "C:\\Program Files (x86)\\Diablo IV".to_string(),
-            launcher_id: "battlenet".to_string(),
-            icon: None,
-        },
-    ]
+    let start_dir = game.working_dir.as_deref().unwrap_or(&game.install_path);
+    let mut shortcut = Shortcut::new(&game.name, &exe, start_dir, &launch_options);
+    if let Some(icon) = &game.icon {
+        shortcut.set_icon(icon);
+    }
+    shortcut
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    sandbox::init_from_env();
+
     tauri::Builder::default()
+        .setup(|app| {
+            if let Some(dir) = app.path_resolver().app_config_dir() {
+                launchers::custom::set_config_dir(dir);
+            }
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             greet,
             detect_launchers,
             get_games_by_launcher,
             add_games_to_steam,
-            check_steam_path
+            check_steam_path,
+            list_steam_users,
+            list_steam_installations,
+            list_compat_tools,
+            list_wine_prefixes,
+            scan_wine_prefix
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
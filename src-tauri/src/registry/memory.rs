@@ -0,0 +1,114 @@
+// A registry held in memory, filled by hand or from a `.reg` export.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use super::{parse_reg_file, Hive, RegistryBackend};
+
+#[derive(Debug, Clone, Default)]
+struct Key {
+    /// Path as first written, for subkey names.
+    path: String,
+    /// Lowercased value name -> value.
+    values: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MemoryRegistry {
+    /// Keyed by hive and lowercased path.
+    keys: BTreeMap<(HiveKey, String), Key>,
+}
+
+/// `Hive` is not `Ord`; this keeps the map ordered and deterministic.
+type HiveKey = u8;
+
+fn hive_key(hive: Hive) -> HiveKey {
+    match hive {
+        Hive::LocalMachine => 0,
+        Hive::CurrentUser => 1,
+    }
+}
+
+fn normalize(path: &str) -> String {
+    path.trim_matches('\\').to_lowercase()
+}
+
+impl MemoryRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn load_reg_file(path: &Path) -> Result<Self, String> {
+        let data = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let text = decode_text(&data);
+        let mut registry = Self::new();
+        parse_reg_file(&text, &mut registry).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+        Ok(registry)
+    }
+
+    /// Creates a key and all of its parents.
+    pub fn add_key(&mut self, hive: Hive, path: &str) {
+        let path = path.trim_matches('\\');
+        let mut prefix = String::new();
+        for part in path.split('\\').filter(|p| !p.is_empty()) {
+            if !prefix.is_empty() {
+                prefix.push('\\');
+            }
+            prefix.push_str(part);
+            self.keys
+                .entry((hive_key(hive), normalize(&prefix)))
+                .or_insert_with(|| Key { path: prefix.clone(), values: BTreeMap::new() });
+        }
+    }
+
+    pub fn set_string(&mut self, hive: Hive, path: &str, name: &str, value: &str) {
+        self.add_key(hive, path);
+        if let Some(key) = self.keys.get_mut(&(hive_key(hive), normalize(path))) {
+            key.values.insert(name.to_lowercase(), value.to_string());
+        }
+    }
+
+    pub fn remove_key(&mut self, hive: Hive, path: &str) {
+        let prefix = format!("{}\\", normalize(path));
+        let exact = normalize(path);
+        self.keys
+            .retain(|(h, p), _| *h != hive_key(hive) || (*p != exact && !p.starts_with(&prefix)));
+    }
+}
+
+impl RegistryBackend for MemoryRegistry {
+    fn key_exists(&self, hive: Hive, path: &str) -> bool {
+        self.keys.contains_key(&(hive_key(hive), normalize(path)))
+    }
+
+    fn get_string(&self, hive: Hive, path: &str, name: &str) -> Option<String> {
+        self.keys
+            .get(&(hive_key(hive), normalize(path)))?
+            .values
+            .get(&name.to_lowercase())
+            .cloned()
+    }
+
+    fn subkeys(&self, hive: Hive, path: &str) -> Vec<String> {
+        let parent = normalize(path);
+        let prefix = if parent.is_empty() { String::new() } else { format!("{}\\", parent) };
+        self.keys
+            .iter()
+            .filter(|((h, p), _)| *h == hive_key(hive) && p.starts_with(&prefix) && !p[prefix.len()..].contains('\\'))
+            .filter(|((_, p), _)| p.len() > prefix.len())
+            .filter_map(|(_, key)| key.path.rsplit('\\').next().map(str::to_string))
+            .collect()
+    }
+}
+
+/// `.reg` exports from regedit are UTF-16LE with a BOM; older ones and Wine
+/// hives are plain 8-bit text.
+pub(crate) fn decode_text(data: &[u8]) -> String {
+    if let Some(rest) = data.strip_prefix(&[0xFF, 0xFE]) {
+        let units: Vec<u16> = rest.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+        return String::from_utf16_lossy(&units);
+    }
+    let data = data.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(data);
+    String::from_utf8_lossy(data).into_owned()
+}
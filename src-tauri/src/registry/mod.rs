@@ -0,0 +1,192 @@
+// Registry access behind a swappable backend.
+//
+// On Windows the default backend is the live registry. Everywhere else there
+// is no registry to read, so the default is an in-memory map, optionally
+// filled from a `.reg` export named by the NO_MORE_LAUNCHERS_REG environment
+// variable. Callers can install any other backend with `set_backend`.
+//
+// Lookups can also be pointed at another backend for the current thread
+// only with `with_backend`, which is how a Wine prefix is scanned without
+// disturbing anything else.
+//
+// Key paths are relative to the hive and use backslashes, e.g.
+// `SOFTWARE\WOW6432Node\Valve\Steam`. Lookups are case-insensitive like the
+// real registry. A 32-bit Windows install or Wine prefix has no WOW6432Node:
+// 32-bit programs write straight to `SOFTWARE\...` there, so a missing
+// WOW6432Node key is looked up again without that component.
+
+mod memory;
+mod reg_file;
+pub mod wine;
+#[cfg(windows)]
+mod windows;
+
+pub use memory::MemoryRegistry;
+pub use reg_file::parse_reg_file;
+#[cfg(windows)]
+pub use windows::LiveRegistry;
+
+use std::cell::RefCell;
+use std::sync::{Arc, RwLock};
+
+/// Environment variable naming a `.reg` export to load on non-Windows systems.
+pub const REG_FILE_ENV: &str = "NO_MORE_LAUNCHERS_REG";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Hive {
+    LocalMachine,
+    CurrentUser,
+}
+
+impl Hive {
+    /// Parses `HKEY_LOCAL_MACHINE`, `HKLM` and friends.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_uppercase().as_str() {
+            "HKEY_LOCAL_MACHINE" | "HKLM" => Some(Hive::LocalMachine),
+            "HKEY_CURRENT_USER" | "HKCU" => Some(Hive::CurrentUser),
+            _ => None,
+        }
+    }
+
+    /// Splits `HKEY_LOCAL_MACHINE\SOFTWARE\...` into hive and key path.
+    pub fn split_path(full_path: &str) -> Option<(Self, &str)> {
+        let (hive, rest) = full_path.split_once('\\').unwrap_or((full_path, ""));
+        Some((Self::from_name(hive)?, rest))
+    }
+}
+
+pub trait RegistryBackend: Send + Sync {
+    fn key_exists(&self, hive: Hive, path: &str) -> bool;
+
+    /// A string value (REG_SZ or REG_EXPAND_SZ) of a key.
+    fn get_string(&self, hive: Hive, path: &str, name: &str) -> Option<String>;
+
+    /// Names of the direct subkeys of a key.
+    fn subkeys(&self, hive: Hive, path: &str) -> Vec<String>;
+}
+
+fn default_backend() -> Arc<dyn RegistryBackend> {
+    #[cfg(windows)]
+    {
+        Arc::new(LiveRegistry)
+    }
+    #[cfg(not(windows))]
+    {
+        let registry = std::env::var_os(REG_FILE_ENV)
+            .map(|path| MemoryRegistry::load_reg_file(std::path::Path::new(&path)))
+            .transpose()
+            .unwrap_or_else(|e| {
+                println!("{}", e);
+                None
+            })
+            .unwrap_or_default();
+        Arc::new(registry)
+    }
+}
+
+static BACKEND: RwLock<Option<Arc<dyn RegistryBackend>>> = RwLock::new(None);
+
+thread_local! {
+    static SCOPED: RefCell<Option<Arc<dyn RegistryBackend>>> = RefCell::new(None);
+}
+
+/// The backend in use: the one set by an enclosing `with_backend` on this
+/// thread, otherwise the process-wide one, created on first use.
+pub fn backend() -> Arc<dyn RegistryBackend> {
+    if let Some(backend) = SCOPED.with(|scoped| scoped.borrow().clone()) {
+        return backend;
+    }
+    if let Some(backend) = BACKEND.read().unwrap().as_ref() {
+        return backend.clone();
+    }
+    BACKEND.write().unwrap().get_or_insert_with(default_backend).clone()
+}
+
+/// Replaces the backend used by every lookup in the process.
+pub fn set_backend(backend: Arc<dyn RegistryBackend>) {
+    *BACKEND.write().unwrap() = Some(backend);
+}
+
+/// Runs `f` with every lookup on this thread going to `backend`.
+pub fn with_backend<T>(backend: Arc<dyn RegistryBackend>, f: impl FnOnce() -> T) -> T {
+    struct Restore(Option<Arc<dyn RegistryBackend>>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            SCOPED.with(|scoped| *scoped.borrow_mut() = previous);
+        }
+    }
+
+    let _restore = Restore(SCOPED.with(|scoped| scoped.borrow_mut().replace(backend)));
+    f()
+}
+
+pub fn key_exists(hive: Hive, path: &str) -> bool {
+    let backend = backend();
+    backend.key_exists(hive, &resolve(backend.as_ref(), hive, path))
+}
+
+pub fn get_string(hive: Hive, path: &str, name: &str) -> Option<String> {
+    let backend = backend();
+    backend.get_string(hive, &resolve(backend.as_ref(), hive, path), name)
+}
+
+pub fn subkeys(hive: Hive, path: &str) -> Vec<String> {
+    let backend = backend();
+    backend.subkeys(hive, &resolve(backend.as_ref(), hive, path))
+}
+
+/// `path`, or the same path without its `WOW6432Node` component when only
+/// that one exists.
+fn resolve(backend: &dyn RegistryBackend, hive: Hive, path: &str) -> String {
+    if backend.key_exists(hive, path) {
+        return path.to_string();
+    }
+    let parts: Vec<&str> = path.split('\\').collect();
+    let native: Vec<&str> = parts.iter().copied().filter(|p| !p.eq_ignore_ascii_case("WOW6432Node")).collect();
+    if native.len() == parts.len() {
+        return path.to_string();
+    }
+    let native = native.join("\\");
+    if backend.key_exists(hive, &native) {
+        native
+    } else {
+        path.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn falls_back_to_the_native_key_without_wow6432node() {
+        let mut win32 = MemoryRegistry::new();
+        win32.set_string(Hive::LocalMachine, "Software\\GOG.com\\Games\\1207658930", "path", "C:\\GOG Games\\Tactics");
+
+        with_backend(Arc::new(win32), || {
+            let key = "SOFTWARE\\WOW6432Node\\GOG.com\\Games";
+            assert!(key_exists(Hive::LocalMachine, key));
+            assert_eq!(subkeys(Hive::LocalMachine, key), ["1207658930"]);
+            assert_eq!(
+                get_string(Hive::LocalMachine, &format!("{}\\1207658930", key), "path").as_deref(),
+                Some("C:\\GOG Games\\Tactics")
+            );
+            assert!(!key_exists(Hive::LocalMachine, "SOFTWARE\\WOW6432Node\\Valve\\Steam"));
+        });
+    }
+
+    #[test]
+    fn prefers_the_wow6432node_key() {
+        let mut win64 = MemoryRegistry::new();
+        win64.set_string(Hive::LocalMachine, "SOFTWARE\\Valve\\Steam", "InstallPath", "native");
+        win64.set_string(Hive::LocalMachine, "SOFTWARE\\WOW6432Node\\Valve\\Steam", "InstallPath", "wow64");
+
+        with_backend(Arc::new(win64), || {
+            assert_eq!(
+                get_string(Hive::LocalMachine, "SOFTWARE\\WOW6432Node\\Valve\\Steam", "InstallPath").as_deref(),
+                Some("wow64")
+            );
+        });
+    }
+}
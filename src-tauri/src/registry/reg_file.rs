@@ -0,0 +1,124 @@
+// Parser for regedit `.reg` exports ("Windows Registry Editor Version 5.00"
+// and "REGEDIT4"). Only string values are kept; other types are skipped since
+// nothing we detect stores paths in them.
+
+use super::{Hive, MemoryRegistry};
+
+pub fn parse_reg_file(text: &str, registry: &mut MemoryRegistry) -> Result<(), String> {
+    let mut current: Option<(Hive, String)> = None;
+    let mut lines = text.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        let mut line = line.trim().to_string();
+        if line.is_empty() || line.starts_with(';') || line.starts_with("Windows Registry Editor") || line == "REGEDIT4" {
+            continue;
+        }
+
+        // Long values continue on the next line after a trailing backslash.
+        while line.ends_with('\\') && !line.starts_with('[') {
+            line.pop();
+            match lines.next() {
+                Some(next) => line.push_str(next.trim()),
+                None => break,
+            }
+        }
+
+        if let Some(section) = line.strip_prefix('[') {
+            let section = section
+                .strip_suffix(']')
+                .ok_or_else(|| format!("Unterminated key line: {}", line))?;
+            // "[-HKEY_...]" deletes a key.
+            let (delete, section) = match section.strip_prefix('-') {
+                Some(rest) => (true, rest),
+                None => (false, section),
+            };
+            current = match Hive::split_path(section) {
+                Some((hive, path)) if delete => {
+                    registry.remove_key(hive, path);
+                    None
+                }
+                Some((hive, path)) => {
+                    registry.add_key(hive, path);
+                    Some((hive, path.to_string()))
+                }
+                None => None,
+            };
+            continue;
+        }
+
+        let Some((hive, path)) = &current else {
+            continue;
+        };
+        let Some((name, value)) = split_value_line(&line) else {
+            continue;
+        };
+        if let Some(value) = parse_string_value(value) {
+            registry.set_string(*hive, path, &name, &value);
+        }
+    }
+
+    Ok(())
+}
+
+/// Splits `"Name"=...` or `@=...` into the value name and the raw value.
+pub(crate) fn split_value_line(line: &str) -> Option<(String, &str)> {
+    if let Some(rest) = line.strip_prefix('@') {
+        return Some((String::new(), rest.trim_start().strip_prefix('=')?));
+    }
+
+    let rest = line.strip_prefix('"')?;
+    let (name, end) = read_quoted(rest)?;
+    let value = rest[end..].trim_start().strip_prefix('=')?;
+    Some((name, value.trim()))
+}
+
+/// Decodes a quoted `"..."` value with `\\` and `\"` escapes. Other value
+/// types (`dword:`, `hex:`) return None.
+pub(crate) fn parse_string_value(value: &str) -> Option<String> {
+    let rest = value.trim().strip_prefix('"')?;
+    read_quoted(rest).map(|(text, _)| text)
+}
+
+/// Reads up to the closing quote; returns the unescaped text and the offset
+/// just past the quote.
+fn read_quoted(rest: &str) -> Option<(String, usize)> {
+    read_until(rest, '"')
+}
+
+/// Unescapes text up to `end`. Besides `\\` and `\"` this understands the
+/// `\n`, `\r`, `\t`, `\0` and `\xHHHH` escapes Wine writes in its hives, and
+/// `\uHHHH` as an alias of `\x`.
+pub(crate) fn read_until(rest: &str, end: char) -> Option<(String, usize)> {
+    let mut text = String::new();
+    let mut chars = rest.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c == end {
+            return Some((text, i + 1));
+        }
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next()?.1 {
+            'n' => text.push('\n'),
+            'r' => text.push('\r'),
+            't' => text.push('\t'),
+            '0' => {}
+            'x' | 'u' => {
+                let mut code = 0u32;
+                for _ in 0..4 {
+                    match chars.peek().and_then(|(_, c)| c.to_digit(16)) {
+                        Some(digit) => {
+                            code = code * 16 + digit;
+                            chars.next();
+                        }
+                        None => break,
+                    }
+                }
+                text.extend(char::from_u32(code));
+            }
+            other => text.push(other),
+        }
+    }
+    None
+}
@@ -0,0 +1,34 @@
+// The live Windows registry.
+
+use winreg::enums::*;
+use winreg::RegKey;
+
+use super::{Hive, RegistryBackend};
+
+pub struct LiveRegistry;
+
+impl LiveRegistry {
+    fn root(hive: Hive) -> RegKey {
+        match hive {
+            Hive::LocalMachine => RegKey::predef(HKEY_LOCAL_MACHINE),
+            Hive::CurrentUser => RegKey::predef(HKEY_CURRENT_USER),
+        }
+    }
+}
+
+impl RegistryBackend for LiveRegistry {
+    fn key_exists(&self, hive: Hive, path: &str) -> bool {
+        Self::root(hive).open_subkey(path).is_ok()
+    }
+
+    fn get_string(&self, hive: Hive, path: &str, name: &str) -> Option<String> {
+        Self::root(hive).open_subkey(path).ok()?.get_value(name).ok()
+    }
+
+    fn subkeys(&self, hive: Hive, path: &str) -> Vec<String> {
+        match Self::root(hive).open_subkey(path) {
+            Ok(key) => key.enum_keys().flatten().collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,173 @@
+// Wine keeps the registry of a prefix in text hives: `system.reg` holds
+// HKEY_LOCAL_MACHINE and `user.reg` holds HKEY_CURRENT_USER. The format is
+// close to a `.reg` export, but key names are relative to the hive, have
+// their backslashes escaped and are followed by a timestamp:
+//
+//     WINE REGISTRY Version 2
+//     ;; All keys relative to \\Machine
+//     #arch=win64
+//
+//     [Software\\Wow6432Node\\Valve\\Steam] 1700000000
+//     #time=1da0b1c2d3e4f50
+//     "InstallPath"="C:\\Program Files (x86)\\Steam"
+//     "Language"=str(2):"%LANG%"
+
+use std::fs;
+use std::path::Path;
+
+use super::memory::decode_text;
+use super::reg_file::{read_until, split_value_line};
+use super::{Hive, MemoryRegistry};
+
+const HEADER: &str = "WINE REGISTRY Version 2";
+
+/// Adds the keys of one hive file to `registry`.
+pub fn parse_hive(text: &str, hive: Hive, registry: &mut MemoryRegistry) -> Result<(), String> {
+    let mut lines = text.lines();
+    match lines.next().map(str::trim) {
+        Some(HEADER) => {}
+        other => return Err(format!("Not a Wine registry hive (header {:?})", other.unwrap_or(""))),
+    }
+
+    let mut current: Option<String> = None;
+    for line in lines {
+        let line = line.trim();
+        if let Some(section) = line.strip_prefix('[') {
+            current = read_until(section, ']').map(|(path, _)| path);
+            if let Some(path) = &current {
+                registry.add_key(hive, path);
+            }
+            continue;
+        }
+
+        let Some(path) = &current else {
+            continue;
+        };
+        let Some((name, value)) = split_value_line(line) else {
+            continue;
+        };
+        // REG_SZ is written bare, REG_EXPAND_SZ and REG_MULTI_SZ as str(n):.
+        let value = value
+            .strip_prefix("str(2):")
+            .or_else(|| value.strip_prefix("str(7):"))
+            .unwrap_or(value);
+        let Some(rest) = value.strip_prefix('"') else {
+            continue;
+        };
+        if let Some((value, _)) = read_until(rest, '"') {
+            registry.set_string(hive, path, &name, &value);
+        }
+    }
+
+    Ok(())
+}
+
+/// Loads `system.reg` and `user.reg` of the prefix at `prefix`. A prefix
+/// without `system.reg` is an error; a missing `user.reg` just leaves
+/// HKEY_CURRENT_USER empty.
+pub fn load_prefix(prefix: &Path) -> Result<MemoryRegistry, String> {
+    let mut registry = MemoryRegistry::new();
+    for (file, hive, required) in [("system.reg", Hive::LocalMachine, true), ("user.reg", Hive::CurrentUser, false)] {
+        let path = prefix.join(file);
+        let data = match fs::read(&path) {
+            Ok(data) => data,
+            Err(_) if !required => continue,
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+        };
+        parse_hive(&decode_text(&data), hive, &mut registry)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+    }
+    Ok(registry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::RegistryBackend;
+    use crate::test_support::TempDir;
+
+    const SYSTEM_REG: &str = "WINE REGISTRY Version 2\n\
+;; All keys relative to \\\\Machine\n\
+\n\
+#arch=win64\n\
+\n\
+[Software\\\\Wow6432Node\\\\Valve\\\\Steam] 1700000000\n\
+#time=1da0b1c2d3e4f50\n\
+\"InstallPath\"=\"C:\\\\Program Files (x86)\\\\Steam\"\n\
+\"Language\"=str(2):\"%LANG%\"\n\
+\"Version\"=dword:00000002\n\
+\n\
+[Software\\\\Caf\\x00e9\\\\Jeux\\\\Caf\\u00e9 Quest] 1700000000\n\
+\"Name\"=\"Caf\\x00e9 Quest\"\n\
+\"Motd\"=\"line one\\nline \\\"two\\\"\"\n";
+
+    fn parse(text: &str) -> MemoryRegistry {
+        let mut registry = MemoryRegistry::new();
+        parse_hive(text, Hive::LocalMachine, &mut registry).unwrap();
+        registry
+    }
+
+    #[test]
+    fn unescapes_key_names() {
+        let registry = parse(SYSTEM_REG);
+        assert!(registry.key_exists(Hive::LocalMachine, "Software\\Wow6432Node\\Valve\\Steam"));
+        assert!(registry.key_exists(Hive::LocalMachine, "Software\\Wow6432Node"));
+        assert_eq!(registry.subkeys(Hive::LocalMachine, "Software\\Café\\Jeux"), ["Café Quest"]);
+        assert!(!registry.key_exists(Hive::CurrentUser, "Software\\Wow6432Node\\Valve\\Steam"));
+    }
+
+    #[test]
+    fn reads_string_values() {
+        let registry = parse(SYSTEM_REG);
+        let steam = "Software\\Wow6432Node\\Valve\\Steam";
+        assert_eq!(
+            registry.get_string(Hive::LocalMachine, steam, "InstallPath").as_deref(),
+            Some("C:\\Program Files (x86)\\Steam")
+        );
+        assert_eq!(registry.get_string(Hive::LocalMachine, steam, "Language").as_deref(), Some("%LANG%"));
+        assert_eq!(registry.get_string(Hive::LocalMachine, steam, "Version"), None);
+
+        let quest = "Software\\Café\\Jeux\\Café Quest";
+        assert_eq!(registry.get_string(Hive::LocalMachine, quest, "Name").as_deref(), Some("Café Quest"));
+        assert_eq!(
+            registry.get_string(Hive::LocalMachine, quest, "Motd").as_deref(),
+            Some("line one\nline \"two\"")
+        );
+    }
+
+    #[test]
+    fn lookups_ignore_case() {
+        let registry = parse(SYSTEM_REG);
+        assert_eq!(
+            registry.get_string(Hive::LocalMachine, "SOFTWARE\\WOW6432Node\\valve\\STEAM", "installpath").as_deref(),
+            Some("C:\\Program Files (x86)\\Steam")
+        );
+        assert!(registry.key_exists(Hive::LocalMachine, "software\\CAFÉ\\jeux"));
+    }
+
+    #[test]
+    fn rejects_files_without_the_header() {
+        let mut registry = MemoryRegistry::new();
+        assert!(parse_hive("REGEDIT4\n", Hive::LocalMachine, &mut registry).is_err());
+    }
+
+    #[test]
+    fn loads_user_reg_when_present() {
+        let prefix = TempDir::new("wine-hive");
+        assert!(load_prefix(&prefix).is_err());
+
+        fs::write(prefix.join("system.reg"), SYSTEM_REG).unwrap();
+        let registry = load_prefix(&prefix).unwrap();
+        assert!(registry.key_exists(Hive::LocalMachine, "Software\\Wow6432Node\\Valve\\Steam"));
+        assert!(registry.subkeys(Hive::CurrentUser, "").is_empty());
+
+        fs::write(
+            prefix.join("user.reg"),
+            "WINE REGISTRY Version 2\n[Software\\\\Epic Games\\\\EOS] 1700000000\n\"ModSdkCommand\"=\"x\"\n",
+        )
+        .unwrap();
+        let registry = load_prefix(&prefix).unwrap();
+        assert!(registry.key_exists(Hive::CurrentUser, "Software\\Epic Games\\EOS"));
+        assert!(!registry.key_exists(Hive::LocalMachine, "Software\\Epic Games\\EOS"));
+    }
+}
@@ -0,0 +1,101 @@
+// Fixture root standing in for a whole machine.
+//
+// When a root is set, every lookup the detectors make goes below it instead
+// of to the real system, so a directory of fixtures can play a gaming PC.
+// The layout follows a Wine prefix, plus the user's home folder:
+//
+//   <root>/drive_c/...        C:\
+//   <root>/dosdevices/d:/...  other drive letters
+//   <root>/home/...           ~ (Linux launchers and Steam)
+//   <root>/config/            app config folder (launchers.json)
+//   <root>/registry.reg       regedit export, or system.reg/user.reg hives
+//
+// The root is set for the whole process from NO_MORE_LAUNCHERS_ROOT, or for
+// the current thread with `with_root`, which is what the tests use.
+
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use crate::registry::{self, MemoryRegistry, RegistryBackend};
+
+/// Environment variable pointing at a fixture root for the whole process.
+pub const ROOT_ENV: &str = "NO_MORE_LAUNCHERS_ROOT";
+
+const REG_FILE: &str = "registry.reg";
+
+static ROOT: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+thread_local! {
+    static SCOPED: RefCell<Option<PathBuf>> = RefCell::new(None);
+}
+
+/// The fixture root in use: the one set by an enclosing `with_root` on this
+/// thread, otherwise the process-wide one.
+pub fn root() -> Option<PathBuf> {
+    SCOPED
+        .with(|scoped| scoped.borrow().clone())
+        .or_else(|| ROOT.read().unwrap().clone())
+}
+
+/// Sends every lookup in the process to `root`.
+pub fn set_root(root: impl Into<PathBuf>) -> Result<(), String> {
+    let root = root.into();
+    let registry = load_registry(&root)?;
+    registry::set_backend(registry);
+    *ROOT.write().unwrap() = Some(root);
+    Ok(())
+}
+
+/// Runs `f` with every lookup on this thread going to `root`.
+pub fn with_root<T>(root: impl Into<PathBuf>, f: impl FnOnce() -> T) -> Result<T, String> {
+    struct Restore(Option<PathBuf>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            SCOPED.with(|scoped| *scoped.borrow_mut() = previous);
+        }
+    }
+
+    let root = root.into();
+    let registry = load_registry(&root)?;
+    let _restore = Restore(SCOPED.with(|scoped| scoped.borrow_mut().replace(root)));
+    Ok(registry::with_backend(registry, f))
+}
+
+/// Sets the process-wide root from `NO_MORE_LAUNCHERS_ROOT`, if present.
+pub fn init_from_env() {
+    if let Some(root) = std::env::var_os(ROOT_ENV) {
+        match set_root(PathBuf::from(&root)) {
+            Ok(()) => println!("Using fixture root {}", Path::new(&root).display()),
+            Err(e) => println!("Ignoring {}: {}", ROOT_ENV, e),
+        }
+    }
+}
+
+/// Where an absolute Linux system path such as /usr/share/... lives: below
+/// the fixture root if one is set, otherwise the path itself.
+pub fn system_path(path: &str) -> PathBuf {
+    match root() {
+        Some(root) => root.join(path.trim_start_matches('/')),
+        None => path.into(),
+    }
+}
+
+/// The registry of a fixture root: `registry.reg` if there is one, then the
+/// Wine hives, otherwise an empty registry.
+pub fn load_registry(root: &Path) -> Result<Arc<dyn RegistryBackend>, String> {
+    if !root.is_dir() {
+        return Err(format!("Fixture root not found: {}", root.display()));
+    }
+
+    let reg_file = root.join(REG_FILE);
+    let registry = if reg_file.is_file() {
+        MemoryRegistry::load_reg_file(&reg_file)?
+    } else if root.join("system.reg").is_file() {
+        registry::wine::load_prefix(root)?
+    } else {
+        MemoryRegistry::new()
+    };
+    Ok(Arc::new(registry))
+}
@@ -0,0 +1,92 @@
+// Ids Steam derives for non-Steam shortcuts.
+//
+// The shortcut appid is the CRC32 of the quoted exe followed by the app name,
+// with the top bit set. shortcuts.vdf stores it as a signed 32-bit int, the
+// legacy 64-bit game id (used by steam://rungameid/) puts it in the upper half
+// with the "shortcut" type bits set, and grid artwork is named after it.
+
+use serde::{Deserialize, Serialize};
+
+const SHORTCUT_APP_ID_BIT: u32 = 0x8000_0000;
+const SHORTCUT_GAME_ID_TYPE: u64 = 0x0200_0000;
+
+/// 32-bit shortcut appid for the exe/name pair exactly as written to shortcuts.vdf.
+pub fn shortcut_app_id(exe: &str, app_name: &str) -> u32 {
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(exe.as_bytes());
+    hasher.update(app_name.as_bytes());
+    hasher.finalize() | SHORTCUT_APP_ID_BIT
+}
+
+/// Value of the `appid` field in shortcuts.vdf.
+pub fn to_shortcut_field(app_id: u32) -> i32 {
+    app_id as i32
+}
+
+pub fn from_shortcut_field(value: i32) -> u32 {
+    value as u32
+}
+
+/// Legacy 64-bit game id, as used in `steam://rungameid/<id>` and old grid art.
+pub fn legacy_game_id(app_id: u32) -> u64 {
+    (u64::from(app_id) << 32) | SHORTCUT_GAME_ID_TYPE
+}
+
+/// File name prefix for artwork in `userdata/<id>/config/grid`, e.g.
+/// `<prefix>p.png` for the portrait capsule and `<prefix>_hero.png`.
+pub fn grid_prefix(app_id: u32) -> String {
+    app_id.to_string()
+}
+
+pub fn rungameid_url(app_id: u32) -> String {
+    format!("steam://rungameid/{}", legacy_game_id(app_id))
+}
+
+/// All ids for one shortcut, in the form handed to the frontend. The 64-bit id
+/// is a string because it does not fit in a JavaScript number.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShortcutIds {
+    pub app_id: u32,
+    pub shortcut_app_id: i32,
+    pub game_id: String,
+    pub run_url: String,
+    pub grid_prefix: String,
+}
+
+impl ShortcutIds {
+    pub fn new(app_id: u32) -> Self {
+        Self {
+            app_id,
+            shortcut_app_id: to_shortcut_field(app_id),
+            game_id: legacy_game_id(app_id).to_string(),
+            run_url: rungameid_url(app_id),
+            grid_prefix: grid_prefix(app_id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_steam_ids_for_a_known_shortcut() {
+        let exe = "\"C:\\Program Files\\Fixture Quest\\FixtureQuest.exe\"";
+        let app_id = shortcut_app_id(exe, "Fixture Quest");
+
+        assert_eq!(app_id, 2502017475);
+        assert_eq!(to_shortcut_field(app_id), -1792949821);
+        assert_eq!(from_shortcut_field(-1792949821), app_id);
+        assert_eq!(legacy_game_id(app_id), 10746083229179052032);
+        assert_eq!(grid_prefix(app_id), "2502017475");
+        assert_eq!(rungameid_url(app_id), "steam://rungameid/10746083229179052032");
+    }
+
+    #[test]
+    fn quoting_changes_the_appid() {
+        let quoted = shortcut_app_id("\"C:\\Games\\Game.exe\"", "Game");
+        let bare = shortcut_app_id("C:\\Games\\Game.exe", "Game");
+        assert_ne!(quoted, bare);
+        assert!(quoted & SHORTCUT_APP_ID_BIT != 0 && bare & SHORTCUT_APP_ID_BIT != 0);
+    }
+}
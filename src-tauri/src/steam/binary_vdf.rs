@@ -0,0 +1,405 @@
+// Binary KeyValues ("binary VDF") as used by Steam for shortcuts.vdf.
+//
+// Every entry is a type byte, a NUL-terminated key and a type-specific payload.
+// Maps are closed with an END byte. Entries keep their original order and type
+// so that a parse/serialize round trip reproduces the input byte-for-byte.
+
+const TYPE_MAP: u8 = 0x00;
+const TYPE_STRING: u8 = 0x01;
+const TYPE_INT32: u8 = 0x02;
+const TYPE_FLOAT32: u8 = 0x03;
+const TYPE_POINTER: u8 = 0x04;
+const TYPE_WIDE_STRING: u8 = 0x05;
+const TYPE_COLOR: u8 = 0x06;
+const TYPE_UINT64: u8 = 0x07;
+const TYPE_END: u8 = 0x08;
+const TYPE_INT64: u8 = 0x0A;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Map(Map),
+    String(String),
+    /// A string that is not valid UTF-8, such as a title older clients stored
+    /// in the system code page. Kept as bytes so it is written back unchanged.
+    RawString(Vec<u8>),
+    Int32(i32),
+    Float32(f32),
+    Pointer(u32),
+    WideString(Vec<u16>),
+    Color(u32),
+    UInt64(u64),
+    Int64(i64),
+}
+
+impl Value {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_i32(&self) -> Option<i32> {
+        match self {
+            Value::Int32(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_map(&self) -> Option<&Map> {
+        match self {
+            Value::Map(m) => Some(m),
+            _ => None,
+        }
+    }
+}
+
+/// Ordered list of key/value pairs. Keys are compared case-insensitively on
+/// lookup, matching Steam, but stored exactly as read.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Map {
+    entries: Vec<(String, Value)>,
+}
+
+impl Map {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        self.entries
+            .iter_mut()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v)
+    }
+
+    /// Replaces the value of an existing key (keeping its spelling and position)
+    /// or appends a new entry.
+    pub fn insert(&mut self, key: &str, value: Value) {
+        match self.get_mut(key) {
+            Some(existing) => *existing = value,
+            None => self.entries.push((key.to_string(), value)),
+        }
+    }
+
+    pub fn push(&mut self, key: String, value: Value) {
+        self.entries.push((key, value));
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        let index = self.entries.iter().position(|(k, _)| k.eq_ignore_ascii_case(key))?;
+        Some(self.entries.remove(index).1)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Value)> {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut Value> {
+        self.entries.iter_mut().map(|(_, v)| v)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Parses a complete binary VDF document. The outermost level is a map that is
+/// terminated either by an END byte or by the end of the input.
+pub fn parse(data: &[u8]) -> Result<Map, String> {
+    let mut reader = Reader { data, pos: 0 };
+    let root = reader.read_map(true)?;
+    if reader.pos != data.len() {
+        return Err(format!("Trailing data at offset {}", reader.pos));
+    }
+    Ok(root)
+}
+
+/// Serializes a map written by `parse` (or built by hand) back to bytes. The
+/// root is closed with an END byte, as Steam does.
+pub fn serialize(root: &Map) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_map(&mut out, root);
+    out
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn read_map(&mut self, is_root: bool) -> Result<Map, String> {
+        let mut map = Map::new();
+        loop {
+            let Some(&kind) = self.data.get(self.pos) else {
+                if is_root {
+                    return Ok(map);
+                }
+                return Err("Unexpected end of data inside a map".to_string());
+            };
+            self.pos += 1;
+
+            if kind == TYPE_END {
+                return Ok(map);
+            }
+
+            // Keys are ASCII field names and indices in practice.
+            let key = String::from_utf8_lossy(self.read_cstring()?).into_owned();
+            let value = match kind {
+                TYPE_MAP => Value::Map(self.read_map(false)?),
+                TYPE_STRING => match String::from_utf8(self.read_cstring()?.to_vec()) {
+                    Ok(s) => Value::String(s),
+                    Err(e) => Value::RawString(e.into_bytes()),
+                },
+                TYPE_INT32 => Value::Int32(i32::from_le_bytes(self.read_array()?)),
+                TYPE_FLOAT32 => Value::Float32(f32::from_le_bytes(self.read_array()?)),
+                TYPE_POINTER => Value::Pointer(u32::from_le_bytes(self.read_array()?)),
+                TYPE_WIDE_STRING => Value::WideString(self.read_wide_string()?),
+                TYPE_COLOR => Value::Color(u32::from_le_bytes(self.read_array()?)),
+                TYPE_UINT64 => Value::UInt64(u64::from_le_bytes(self.read_array()?)),
+                TYPE_INT64 => Value::Int64(i64::from_le_bytes(self.read_array()?)),
+                other => {
+                    return Err(format!(
+                        "Unknown field type 0x{:02x} for key '{}' at offset {}",
+                        other,
+                        key,
+                        self.pos
+                    ))
+                }
+            };
+            map.push(key, value);
+        }
+    }
+
+    fn read_cstring(&mut self) -> Result<&'a [u8], String> {
+        let rest = &self.data[self.pos..];
+        let len = rest
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or_else(|| format!("Unterminated string at offset {}", self.pos))?;
+        self.pos += len + 1;
+        Ok(&rest[..len])
+    }
+
+    fn read_wide_string(&mut self) -> Result<Vec<u16>, String> {
+        let mut units = Vec::new();
+        loop {
+            let unit = u16::from_le_bytes(self.read_array()?);
+            if unit == 0 {
+                return Ok(units);
+            }
+            units.push(unit);
+        }
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let bytes = self
+            .data
+            .get(self.pos..self.pos + N)
+            .ok_or_else(|| format!("Unexpected end of data at offset {}", self.pos))?;
+        self.pos += N;
+        Ok(bytes.try_into().unwrap())
+    }
+}
+
+fn write_map(out: &mut Vec<u8>, map: &Map) {
+    for (key, value) in map.iter() {
+        let kind = match value {
+            Value::Map(_) => TYPE_MAP,
+            Value::String(_) | Value::RawString(_) => TYPE_STRING,
+            Value::Int32(_) => TYPE_INT32,
+            Value::Float32(_) => TYPE_FLOAT32,
+            Value::Pointer(_) => TYPE_POINTER,
+            Value::WideString(_) => TYPE_WIDE_STRING,
+            Value::Color(_) => TYPE_COLOR,
+            Value::UInt64(_) => TYPE_UINT64,
+            Value::Int64(_) => TYPE_INT64,
+        };
+        out.push(kind);
+        write_cstring(out, key.as_bytes());
+
+        match value {
+            Value::Map(m) => write_map(out, m),
+            Value::String(s) => write_cstring(out, s.as_bytes()),
+            Value::RawString(bytes) => write_cstring(out, bytes),
+            Value::Int32(v) => out.extend_from_slice(&v.to_le_bytes()),
+            Value::Float32(v) => out.extend_from_slice(&v.to_le_bytes()),
+            Value::Pointer(v) | Value::Color(v) => out.extend_from_slice(&v.to_le_bytes()),
+            Value::WideString(units) => {
+                for unit in units {
+                    out.extend_from_slice(&unit.to_le_bytes());
+                }
+                out.extend_from_slice(&[0, 0]);
+            }
+            Value::UInt64(v) => out.extend_from_slice(&v.to_le_bytes()),
+            Value::Int64(v) => out.extend_from_slice(&v.to_le_bytes()),
+        }
+    }
+    out.push(TYPE_END);
+}
+
+fn write_cstring(out: &mut Vec<u8>, s: &[u8]) {
+    out.extend_from_slice(s);
+    out.push(0);
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Writes binary VDF by hand, so tests compare against bytes that did not
+    /// come from `serialize`.
+    #[derive(Default)]
+    pub(crate) struct Builder(Vec<u8>);
+
+    impl Builder {
+        fn key(mut self, kind: u8, key: &str) -> Self {
+            self.0.push(kind);
+            self.0.extend_from_slice(key.as_bytes());
+            self.0.push(0);
+            self
+        }
+
+        pub(crate) fn map(self, key: &str) -> Self {
+            self.key(TYPE_MAP, key)
+        }
+
+        pub(crate) fn end(mut self) -> Self {
+            self.0.push(TYPE_END);
+            self
+        }
+
+        pub(crate) fn string(self, key: &str, value: &str) -> Self {
+            self.raw_string(key, value.as_bytes())
+        }
+
+        pub(crate) fn raw_string(self, key: &str, value: &[u8]) -> Self {
+            let mut b = self.key(TYPE_STRING, key);
+            b.0.extend_from_slice(value);
+            b.0.push(0);
+            b
+        }
+
+        pub(crate) fn int32(self, key: &str, value: i32) -> Self {
+            let mut b = self.key(TYPE_INT32, key);
+            b.0.extend_from_slice(&value.to_le_bytes());
+            b
+        }
+
+        pub(crate) fn float32(self, key: &str, value: f32) -> Self {
+            let mut b = self.key(TYPE_FLOAT32, key);
+            b.0.extend_from_slice(&value.to_le_bytes());
+            b
+        }
+
+        pub(crate) fn uint64(self, key: &str, value: u64) -> Self {
+            let mut b = self.key(TYPE_UINT64, key);
+            b.0.extend_from_slice(&value.to_le_bytes());
+            b
+        }
+
+        pub(crate) fn wide_string(self, key: &str, value: &str) -> Self {
+            let mut b = self.key(TYPE_WIDE_STRING, key);
+            for unit in value.encode_utf16() {
+                b.0.extend_from_slice(&unit.to_le_bytes());
+            }
+            b.0.extend_from_slice(&[0, 0]);
+            b
+        }
+
+        pub(crate) fn build(self) -> Vec<u8> {
+            self.0
+        }
+    }
+
+    fn every_type() -> Vec<u8> {
+        Builder::default()
+            .map("root")
+            .string("Name", "Fixture Quest")
+            .int32("Negative", -42)
+            .float32("Scale", 1.5)
+            .uint64("Big", 0xFEDC_BA98_7654_3210)
+            .wide_string("Wide", "Fixture — Ω")
+            .map("tags")
+            .string("0", "Favorite")
+            .end()
+            .end()
+            .end()
+            .build()
+    }
+
+    #[test]
+    fn parses_every_value_type() {
+        let doc = parse(&every_type()).unwrap();
+        let root = doc.get("root").and_then(Value::as_map).unwrap();
+        assert_eq!(root.get("name").and_then(Value::as_str), Some("Fixture Quest"));
+        assert_eq!(root.get("Negative"), Some(&Value::Int32(-42)));
+        assert_eq!(root.get("Scale"), Some(&Value::Float32(1.5)));
+        assert_eq!(root.get("Big"), Some(&Value::UInt64(0xFEDC_BA98_7654_3210)));
+        assert_eq!(root.get("Wide"), Some(&Value::WideString("Fixture — Ω".encode_utf16().collect())));
+        let tags = root.get("tags").and_then(Value::as_map).unwrap();
+        assert_eq!(tags.get("0").and_then(Value::as_str), Some("Favorite"));
+    }
+
+    #[test]
+    fn round_trips_byte_for_byte() {
+        let data = every_type();
+        assert_eq!(serialize(&parse(&data).unwrap()), data);
+    }
+
+    #[test]
+    fn keeps_strings_that_are_not_utf8() {
+        // "Café" in Windows-1252, as older clients stored non-ASCII titles
+        let data = Builder::default()
+            .map("0")
+            .raw_string("AppName", b"Caf\xe9")
+            .string("Exe", "cafe.exe")
+            .end()
+            .end()
+            .build();
+        let doc = parse(&data).unwrap();
+        let entry = doc.get("0").and_then(Value::as_map).unwrap();
+        assert_eq!(entry.get("AppName"), Some(&Value::RawString(b"Caf\xe9".to_vec())));
+        assert_eq!(entry.get("AppName").and_then(Value::as_str), None);
+        assert_eq!(entry.get("Exe").and_then(Value::as_str), Some("cafe.exe"));
+        assert_eq!(serialize(&doc), data);
+    }
+
+    #[test]
+    fn insert_keeps_key_spelling_and_position() {
+        let mut doc = parse(&every_type()).unwrap();
+        let Some(Value::Map(root)) = doc.get_mut("root") else {
+            panic!("root is not a map");
+        };
+        root.insert("name", Value::String("Renamed".to_string()));
+        let keys: Vec<&str> = root.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(keys, ["Name", "Negative", "Scale", "Big", "Wide", "tags"]);
+    }
+
+    #[test]
+    fn rejects_truncated_input() {
+        // The root map may end at end of input, nested ones may not
+        let data = every_type();
+        for len in [3, 12, data.len() - 2] {
+            assert!(parse(&data[..len]).is_err(), "accepted {} bytes", len);
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_types() {
+        let data = Builder::default().key(0x09, "odd").build();
+        assert!(parse(&data).unwrap_err().contains("0x09"));
+    }
+}
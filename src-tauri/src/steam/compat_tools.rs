@@ -0,0 +1,186 @@
+// Compatibility tools (Proton and friends) and the per-app tool choice.
+//
+// Custom tools such as GE-Proton are folders under `compatibilitytools.d`,
+// each described by a `compatibilitytool.vdf`. Valve's Proton builds are
+// regular Steam apps in `steamapps/common`. Which tool an app runs with is
+// stored in `config/config.vdf`:
+//
+//     "InstallConfigStore" { "Software" { "Valve" { "Steam" {
+//         "CompatToolMapping" { "<appid>" { "name" "proton_9" "config" "" "priority" "250" } }
+//     } } } }
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::library_folders;
+use super::vdf::{Document, Object, Value};
+use crate::sandbox;
+
+const MAPPING_PATH: [&str; 5] = ["InstallConfigStore", "Software", "Valve", "Steam", "CompatToolMapping"];
+
+/// Priority Steam writes when the user picks a tool for an app.
+const USER_PRIORITY: &str = "250";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompatToolSource {
+    /// From a `compatibilitytools.d` folder.
+    Custom,
+    /// A Proton build installed through Steam.
+    Proton,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompatTool {
+    /// Internal name, the value written to CompatToolMapping.
+    pub name: String,
+    pub display_name: String,
+    pub path: String,
+    pub source: CompatToolSource,
+}
+
+pub fn config_path(steam_path: &Path) -> PathBuf {
+    steam_path.join("config").join("config.vdf")
+}
+
+/// Every tool Steam would offer, custom tools first, without duplicate names.
+pub fn list_tools(steam_path: &Path) -> Vec<CompatTool> {
+    let mut tools: Vec<CompatTool> = Vec::new();
+
+    let custom_dirs = [
+        steam_path.join("compatibilitytools.d"),
+        sandbox::system_path("/usr/share/steam/compatibilitytools.d"),
+        sandbox::system_path("/usr/local/share/steam/compatibilitytools.d"),
+    ];
+    for dir in &custom_dirs {
+        for tool in custom_tools(dir) {
+            if !tools.iter().any(|t| t.name == tool.name) {
+                tools.push(tool);
+            }
+        }
+    }
+
+    for library in library_folders(steam_path) {
+        for tool in proton_builds(&library.join("steamapps").join("common")) {
+            if !tools.iter().any(|t| t.name == tool.name) {
+                tools.push(tool);
+            }
+        }
+    }
+
+    tools
+}
+
+fn custom_tools(dir: &Path) -> Vec<CompatTool> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut folders: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+    folders.sort();
+
+    let mut tools = Vec::new();
+    for folder in folders {
+        let manifest = folder.join("compatibilitytool.vdf");
+        if !manifest.is_file() {
+            continue;
+        }
+        let doc = match Document::load(&manifest) {
+            Ok(doc) => doc,
+            Err(e) => {
+                println!("{}", e);
+                continue;
+            }
+        };
+        let Some(Value::Object(compat_tools)) = doc.root().get_path(&["compatibilitytools", "compat_tools"]) else {
+            continue;
+        };
+        for (name, value) in compat_tools.iter() {
+            let Some(tool) = value.as_object() else {
+                continue;
+            };
+            tools.push(CompatTool {
+                name: name.to_string(),
+                display_name: tool.get_str("display_name").unwrap_or(name).to_string(),
+                path: folder.to_string_lossy().to_string(),
+                source: CompatToolSource::Custom,
+            });
+        }
+    }
+    tools
+}
+
+fn proton_builds(common: &Path) -> Vec<CompatTool> {
+    let Ok(entries) = fs::read_dir(common) else {
+        return Vec::new();
+    };
+
+    let mut tools: Vec<CompatTool> = entries
+        .flatten()
+        .filter(|e| e.path().join("proton").is_file())
+        .filter_map(|e| {
+            let display_name = e.file_name().into_string().ok()?;
+            Some(CompatTool {
+                name: proton_internal_name(&display_name)?,
+                display_name,
+                path: e.path().to_string_lossy().to_string(),
+                source: CompatToolSource::Proton,
+            })
+        })
+        .collect();
+    tools.sort_by(|a, b| a.display_name.cmp(&b.display_name));
+    tools
+}
+
+/// Maps a Proton install folder to the name Steam uses for it:
+/// "Proton 9.0" is `proton_9`, "Proton 5.13" is `proton_513`,
+/// "Proton - Experimental" is `proton_experimental`.
+pub fn proton_internal_name(folder_name: &str) -> Option<String> {
+    let rest = folder_name.strip_prefix("Proton")?.trim_start_matches([' ', '-']).trim();
+    if rest.is_empty() {
+        return None;
+    }
+    if rest.starts_with(|c: char| c.is_ascii_digit()) {
+        let version = rest.split_whitespace().next()?;
+        let (major, minor) = version.split_once('.').unwrap_or((version, "0"));
+        return Some(if minor.trim_matches('0').is_empty() {
+            format!("proton_{}", major)
+        } else {
+            format!("proton_{}{}", major, minor)
+        });
+    }
+    Some(format!("proton_{}", rest.to_lowercase().replace([' ', '-'], "_")))
+}
+
+/// Assigns `tool` to every appid in `app_ids`, as if picked in the game's
+/// compatibility settings. Refuses while Steam is running, since it rewrites
+/// config.vdf when it exits and the change would be lost.
+pub fn set_tool(steam_path: &Path, app_ids: &[u32], tool: &str) -> Result<(), String> {
+    if super::is_running() {
+        return Err("Steam is running and would overwrite config.vdf when it exits. Close Steam and import again".to_string());
+    }
+
+    let path = config_path(steam_path);
+    let mut doc = if path.exists() { Document::load(&path)? } else { Document::new() };
+
+    let mapping = doc.root_mut().object_at_path_mut(&MAPPING_PATH);
+    for app_id in app_ids {
+        let entry = mapping.object_mut_or_insert(&app_id.to_string());
+        set_mapping(entry, tool);
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    doc.save(&path)
+}
+
+fn set_mapping(entry: &mut Object, tool: &str) {
+    entry.set_str("name", tool);
+    if entry.get("config").is_none() {
+        entry.set_str("config", "");
+    }
+    entry.set_str("priority", USER_PRIORITY);
+}
@@ -0,0 +1,57 @@
+// Proton prefixes under `steamapps/compatdata/<appid>`.
+//
+// Steam creates one for every game or shortcut started through Proton. The
+// Wine prefix itself is the `pfx` folder inside it.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::library_folders;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompatData {
+    pub app_id: u32,
+    /// `steamapps/compatdata/<appid>`, the value of STEAM_COMPAT_DATA_PATH.
+    pub path: PathBuf,
+    /// The Wine prefix, `<path>/pfx`.
+    pub prefix: PathBuf,
+}
+
+impl CompatData {
+    /// True if the prefix belongs to a non-Steam shortcut rather than a
+    /// Steam game; shortcut appids have the high bit set.
+    pub fn is_shortcut(&self) -> bool {
+        self.app_id & 0x8000_0000 != 0
+    }
+}
+
+/// Every initialised Proton prefix in every library folder of the Steam
+/// installation at `steam_path`.
+pub fn list(steam_path: &Path) -> Vec<CompatData> {
+    let mut prefixes = Vec::new();
+    for library in library_folders(steam_path) {
+        prefixes.extend(list_library(&library));
+    }
+    prefixes
+}
+
+fn list_library(library: &Path) -> Vec<CompatData> {
+    let Ok(entries) = fs::read_dir(library.join("steamapps").join("compatdata")) else {
+        return Vec::new();
+    };
+
+    let mut prefixes: Vec<CompatData> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let app_id = entry.file_name().to_str()?.parse().ok()?;
+            let path = entry.path();
+            let prefix = path.join("pfx");
+            // Steam creates the folder before Proton has set the prefix up.
+            prefix.join("system.reg").is_file().then_some(CompatData { app_id, path, prefix })
+        })
+        .collect();
+    prefixes.sort_by_key(|p| p.app_id);
+    prefixes
+}
@@ -0,0 +1,126 @@
+// Locating Steam installations.
+//
+// Windows installs come from the registry and the default Program Files
+// folders. On Linux Steam can live in several places at once: the native
+// ~/.steam/steam symlink and ~/.local/share/Steam, the Flatpak data folder
+// and the Snap one. All of them are returned, without duplicates, so the
+// user can choose.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::users::{self, SteamUser};
+use crate::launchers::{home_dir, host_path};
+use crate::registry::{self, Hive};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallKind {
+    Registry,
+    Windows,
+    Native,
+    Flatpak,
+    Snap,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SteamInstallation {
+    pub root: String,
+    pub kind: InstallKind,
+    pub users: Vec<SteamUser>,
+}
+
+/// Every Steam installation on this machine, the registered one first.
+pub fn installations() -> Vec<SteamInstallation> {
+    let mut candidates = Vec::new();
+
+    // Try registry first
+    if let Some(install_path) = registry::get_string(Hive::LocalMachine, "SOFTWARE\\WOW6432Node\\Valve\\Steam", "InstallPath") {
+        candidates.push((host_path(&install_path), InstallKind::Registry));
+    }
+
+    // Then the common Windows and Linux locations
+    candidates.extend(default_candidates());
+    find_installations(candidates)
+}
+
+/// Root of the preferred Steam installation.
+pub fn steam_path() -> Option<String> {
+    installations().into_iter().next().map(|install| install.root)
+}
+
+/// Well-known Steam roots for the current platform, in order of preference.
+pub fn default_candidates() -> Vec<(PathBuf, InstallKind)> {
+    let mut candidates = vec![
+        (host_path("C:\\Program Files (x86)\\Steam"), InstallKind::Windows),
+        (host_path("C:\\Program Files\\Steam"), InstallKind::Windows),
+    ];
+
+    let home = home_dir();
+    if !home.as_os_str().is_empty() {
+        candidates.extend([
+            (home.join(".steam").join("steam"), InstallKind::Native),
+            (home.join(".steam").join("root"), InstallKind::Native),
+            (home.join(".local").join("share").join("Steam"), InstallKind::Native),
+            (
+                home.join(".var").join("app").join("com.valvesoftware.Steam").join("data").join("Steam"),
+                InstallKind::Flatpak,
+            ),
+            (
+                home.join(".var")
+                    .join("app")
+                    .join("com.valvesoftware.Steam")
+                    .join(".local")
+                    .join("share")
+                    .join("Steam"),
+                InstallKind::Flatpak,
+            ),
+            (
+                home.join("snap").join("steam").join("common").join(".local").join("share").join("Steam"),
+                InstallKind::Snap,
+            ),
+            (
+                home.join("snap").join("steam").join("common").join(".steam").join("steam"),
+                InstallKind::Snap,
+            ),
+        ]);
+    }
+
+    candidates
+}
+
+/// A folder counts as a Steam root if it has the folders Steam creates on
+/// first start.
+pub fn is_steam_root(path: &Path) -> bool {
+    path.join("steamapps").is_dir() || path.join("userdata").is_dir() || path.join("config").join("config.vdf").is_file()
+}
+
+/// Every distinct Steam root among `candidates`. Symlinked locations such as
+/// ~/.steam/steam are resolved so the same install is only listed once.
+pub fn find_installations(candidates: Vec<(PathBuf, InstallKind)>) -> Vec<SteamInstallation> {
+    let mut seen: Vec<PathBuf> = Vec::new();
+    let mut installations = Vec::new();
+
+    for (path, kind) in candidates {
+        let trusted = kind == InstallKind::Registry && path.is_dir();
+        if !trusted && !is_steam_root(&path) {
+            continue;
+        }
+
+        let resolved = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+        if seen.contains(&resolved) {
+            continue;
+        }
+        seen.push(resolved);
+
+        installations.push(SteamInstallation {
+            root: path.to_string_lossy().to_string(),
+            kind,
+            users: users::list_users(&path),
+        });
+    }
+
+    installations
+}
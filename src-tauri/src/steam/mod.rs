@@ -0,0 +1,103 @@
+pub mod appid;
+pub mod binary_vdf;
+pub mod compat_tools;
+pub mod compatdata;
+pub mod install;
+pub mod shortcuts;
+pub mod users;
+pub mod vdf;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use vdf::Document;
+
+use crate::launchers::home_dir;
+use crate::sandbox;
+
+/// Account ids that have a folder under `<steam>/userdata`. The "0" folder is
+/// created by Steam before any login and never belongs to a real account.
+pub fn userdata_ids(steam_path: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(steam_path.join("userdata")) else {
+        return Vec::new();
+    };
+
+    let mut ids: Vec<String> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| name != "0" && name.chars().all(|c| c.is_ascii_digit()))
+        .collect();
+    ids.sort();
+    ids
+}
+
+/// Steam library roots listed in `steamapps/libraryfolders.vdf`, starting with
+/// the Steam installation itself. Symlinks are resolved when comparing, so
+/// ~/.steam/steam and the ~/.local/share/Steam it points at count once.
+pub fn library_folders(steam_path: &Path) -> Vec<PathBuf> {
+    let mut folders = vec![steam_path.to_path_buf()];
+    let mut seen = vec![canonical(steam_path)];
+
+    let Ok(doc) = Document::load(&steam_path.join("steamapps").join("libraryfolders.vdf")) else {
+        return folders;
+    };
+    let Some(root) = doc.root().get_object("libraryfolders") else {
+        return folders;
+    };
+
+    for (_, value) in root.iter() {
+        // Current format: "0" { "path" "..." }. Old format: "1" "D:\Games".
+        let path = match value {
+            vdf::Value::Object(folder) => folder.get_str("path"),
+            vdf::Value::String(path) => Some(path.as_str()),
+        };
+        if let Some(path) = path.map(crate::launchers::host_path) {
+            let resolved = canonical(&path);
+            if path.is_dir() && !seen.contains(&resolved) {
+                seen.push(resolved);
+                folders.push(path);
+            }
+        }
+    }
+    folders
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Whether the Steam client is running. It rewrites config.vdf when it exits,
+/// so changes made to that file meanwhile are lost. Steam on Linux keeps its
+/// pid in `~/.steam/steam.pid`; on Windows the process list is checked.
+pub fn is_running() -> bool {
+    if cfg!(windows) && sandbox::root().is_none() {
+        return std::process::Command::new("tasklist")
+            .args(["/FI", "IMAGENAME eq steam.exe", "/NH"])
+            .output()
+            .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).to_lowercase().contains("steam.exe"));
+    }
+
+    let home = home_dir();
+    [
+        home.join(".steam").join("steam.pid"),
+        home.join(".var").join("app").join("com.valvesoftware.Steam").join(".steam").join("steam.pid"),
+    ]
+    .iter()
+    .filter_map(|pid_file| fs::read_to_string(pid_file).ok())
+    .any(|pid| {
+        let pid = pid.trim();
+        // The pid may be stale and reused by another process.
+        !pid.is_empty()
+            && pid.chars().all(|c| c.is_ascii_digit())
+            && fs::read_to_string(sandbox::system_path("/proc").join(pid).join("comm"))
+                .is_ok_and(|comm| comm.trim().starts_with("steam"))
+    })
+}
+
+/// `path` with `suffix` appended to its file name, e.g. `shortcuts.vdf.bak`.
+pub(crate) fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
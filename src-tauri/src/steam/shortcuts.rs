@@ -0,0 +1,356 @@
+// Typed view over userdata/<id>/config/shortcuts.vdf.
+//
+// A shortcut keeps its raw binary VDF map so that keys we do not know about
+// (and the key spelling Steam used) survive a load/save cycle untouched.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::appid;
+use super::binary_vdf::{self, Map, Value};
+use super::with_suffix;
+
+const ROOT_KEY: &str = "shortcuts";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shortcut {
+    fields: Map,
+}
+
+impl Shortcut {
+    /// Builds a new entry with the fields Steam itself writes for a non-Steam game.
+    pub fn new(app_name: &str, exe: &str, start_dir: &str, launch_options: &str) -> Self {
+        let exe = quote(exe);
+        let app_id = appid::shortcut_app_id(&exe, app_name);
+
+        let mut fields = Map::new();
+        fields.push("appid".to_string(), Value::Int32(appid::to_shortcut_field(app_id)));
+        fields.push("AppName".to_string(), Value::String(app_name.to_string()));
+        fields.push("Exe".to_string(), Value::String(exe));
+        fields.push("StartDir".to_string(), Value::String(quote(start_dir)));
+        fields.push("icon".to_string(), Value::String(String::new()));
+        fields.push("ShortcutPath".to_string(), Value::String(String::new()));
+        fields.push("LaunchOptions".to_string(), Value::String(launch_options.to_string()));
+        fields.push("IsHidden".to_string(), Value::Int32(0));
+        fields.push("AllowDesktopConfig".to_string(), Value::Int32(1));
+        fields.push("AllowOverlay".to_string(), Value::Int32(1));
+        fields.push("OpenVR".to_string(), Value::Int32(0));
+        fields.push("Devkit".to_string(), Value::Int32(0));
+        fields.push("DevkitGameID".to_string(), Value::String(String::new()));
+        fields.push("DevkitOverrideAppID".to_string(), Value::Int32(0));
+        fields.push("LastPlayTime".to_string(), Value::Int32(0));
+        fields.push("FlatpakAppID".to_string(), Value::String(String::new()));
+        fields.push("tags".to_string(), Value::Map(Map::new()));
+        Self { fields }
+    }
+
+    pub fn from_map(fields: Map) -> Self {
+        Self { fields }
+    }
+
+    pub fn as_map(&self) -> &Map {
+        &self.fields
+    }
+
+    /// The stored appid, or the one Steam would compute for entries written
+    /// by older tools that left the field out.
+    pub fn app_id(&self) -> u32 {
+        match self.fields.get("appid").and_then(Value::as_i32) {
+            Some(value) => appid::from_shortcut_field(value),
+            None => appid::shortcut_app_id(self.exe(), self.app_name()),
+        }
+    }
+
+    pub fn app_name(&self) -> &str {
+        self.string_field("AppName")
+    }
+
+    /// Executable as stored by Steam, usually wrapped in double quotes.
+    pub fn exe(&self) -> &str {
+        self.string_field("Exe")
+    }
+
+    pub fn start_dir(&self) -> &str {
+        self.string_field("StartDir")
+    }
+
+    pub fn launch_options(&self) -> &str {
+        self.string_field("LaunchOptions")
+    }
+
+    pub fn icon(&self) -> &str {
+        self.string_field("icon")
+    }
+
+    pub fn set_icon(&mut self, icon: &str) {
+        self.fields.insert("icon", Value::String(icon.to_string()));
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        self.fields.get("IsHidden").and_then(Value::as_i32).unwrap_or(0) != 0
+    }
+
+    pub fn tags(&self) -> Vec<&str> {
+        self.fields
+            .get("tags")
+            .and_then(Value::as_map)
+            .map(|tags| tags.iter().filter_map(|(_, v)| v.as_str()).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn add_tag(&mut self, tag: &str) {
+        if self.tags().contains(&tag) {
+            return;
+        }
+        if !matches!(self.fields.get("tags"), Some(Value::Map(_))) {
+            self.fields.insert("tags", Value::Map(Map::new()));
+        }
+        if let Some(Value::Map(tags)) = self.fields.get_mut("tags") {
+            let index = tags.len().to_string();
+            tags.push(index, Value::String(tag.to_string()));
+        }
+    }
+
+    /// True if both entries launch the same program under the same name, which
+    /// is how we avoid adding a game twice.
+    pub fn is_same_game(&self, other: &Shortcut) -> bool {
+        self.app_name() == other.app_name() && unquote(self.exe()).eq_ignore_ascii_case(unquote(other.exe()))
+    }
+
+    fn string_field(&self, key: &str) -> &str {
+        self.fields.get(key).and_then(Value::as_str).unwrap_or("")
+    }
+}
+
+/// The whole shortcuts.vdf file. Anything at the root other than the
+/// `shortcuts` map is kept as-is.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ShortcutsFile {
+    root: Map,
+    shortcuts: Vec<Shortcut>,
+}
+
+impl ShortcutsFile {
+    pub fn parse(data: &[u8]) -> Result<Self, String> {
+        let mut root = binary_vdf::parse(data)?;
+
+        let shortcuts = match root.get_mut(ROOT_KEY) {
+            Some(Value::Map(list)) => {
+                let mut shortcuts = Vec::with_capacity(list.len());
+                for value in list.values_mut() {
+                    match std::mem::replace(value, Value::Map(Map::new())) {
+                        Value::Map(fields) => shortcuts.push(Shortcut::from_map(fields)),
+                        _ => return Err("shortcuts.vdf contains a non-map shortcut entry".to_string()),
+                    }
+                }
+                shortcuts
+            }
+            Some(_) => return Err("shortcuts.vdf root 'shortcuts' is not a map".to_string()),
+            None => Vec::new(),
+        };
+
+        Ok(Self { root, shortcuts })
+    }
+
+    /// Reads the file, treating a missing file as an empty shortcut list.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match fs::read(path) {
+            Ok(data) => Self::parse(&data).map_err(|e| format!("Failed to parse {}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+        }
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        // Steam numbers the entries "0", "1", ... in order; renumber in case
+        // entries were added or removed.
+        let mut list = Map::new();
+        for (index, shortcut) in self.shortcuts.iter().enumerate() {
+            list.push(index.to_string(), Value::Map(shortcut.fields.clone()));
+        }
+
+        let mut root = self.root.clone();
+        root.insert(ROOT_KEY, Value::Map(list));
+        binary_vdf::serialize(&root)
+    }
+
+    /// Writes the file next to a `.bak` copy of the previous version. The new
+    /// contents go to a temporary file first so Steam never sees a half-written file.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+
+        if path.exists() {
+            fs::copy(path, with_suffix(path, ".bak"))
+                .map_err(|e| format!("Failed to back up {}: {}", path.display(), e))?;
+        }
+
+        let tmp = with_suffix(path, ".tmp");
+        fs::write(&tmp, self.serialize()).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+        fs::rename(&tmp, path).map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+    }
+
+    pub fn shortcuts(&self) -> &[Shortcut] {
+        &self.shortcuts
+    }
+
+    pub fn find(&self, shortcut: &Shortcut) -> Option<&Shortcut> {
+        self.shortcuts.iter().find(|s| s.is_same_game(shortcut))
+    }
+
+    pub fn contains(&self, shortcut: &Shortcut) -> bool {
+        self.find(shortcut).is_some()
+    }
+
+    /// Appends the shortcut unless the same game is already present. Returns
+    /// the appid of the entry now in the file and whether it was newly added.
+    pub fn add(&mut self, shortcut: Shortcut) -> (u32, bool) {
+        if let Some(existing) = self.find(&shortcut) {
+            return (existing.app_id(), false);
+        }
+        let app_id = shortcut.app_id();
+        self.shortcuts.push(shortcut);
+        (app_id, true)
+    }
+}
+
+/// Location of shortcuts.vdf for one Steam user.
+pub fn shortcuts_path(steam_path: &Path, user_id: &str) -> PathBuf {
+    steam_path
+        .join("userdata")
+        .join(user_id)
+        .join("config")
+        .join("shortcuts.vdf")
+}
+
+fn quote(value: &str) -> String {
+    if value.is_empty() || value.starts_with('"') {
+        value.to_string()
+    } else {
+        format!("\"{}\"", value)
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    value.trim().trim_matches('"')
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::steam::binary_vdf::tests::Builder;
+
+    /// shortcuts.vdf as Steam writes it, with a second entry carrying keys and
+    /// value types this tool never writes itself.
+    fn steam_file() -> Vec<u8> {
+        Builder::default()
+            .map("shortcuts")
+            .map("0")
+            .int32("appid", -1792949821)
+            .string("AppName", "Fixture Quest")
+            .string("Exe", "\"C:\\Program Files\\Fixture Quest\\FixtureQuest.exe\"")
+            .string("StartDir", "\"C:\\Program Files\\Fixture Quest\\\"")
+            .string("icon", "")
+            .string("ShortcutPath", "")
+            .string("LaunchOptions", "")
+            .int32("IsHidden", 0)
+            .int32("AllowDesktopConfig", 1)
+            .int32("AllowOverlay", 1)
+            .int32("OpenVR", 0)
+            .int32("Devkit", 0)
+            .string("DevkitGameID", "")
+            .int32("DevkitOverrideAppID", 0)
+            .int32("LastPlayTime", 1700000000)
+            .string("FlatpakAppID", "")
+            .string("sortas", "quest")
+            .map("tags")
+            .string("0", "Favorite")
+            .string("1", "RPG")
+            .end()
+            .end()
+            .map("1")
+            .int32("appid", -1234567890)
+            .string("appname", "Orbit Runner")
+            .string("exe", "\"/usr/bin/orbit-runner\"")
+            .string("StartDir", "\"/usr/bin/\"")
+            .uint64("GameID", 0x8000_0001_0200_0000)
+            .float32("Playtime", 12.5)
+            .wide_string("Note", "Orbit — Ω")
+            .map("tags")
+            .end()
+            .end()
+            .end()
+            .end()
+            .build()
+    }
+
+    #[test]
+    fn round_trips_steam_file_byte_for_byte() {
+        let data = steam_file();
+        let file = ShortcutsFile::parse(&data).unwrap();
+        assert_eq!(file.shortcuts().len(), 2);
+        assert_eq!(file.serialize(), data);
+    }
+
+    #[test]
+    fn reads_fields_case_insensitively() {
+        let file = ShortcutsFile::parse(&steam_file()).unwrap();
+        let [quest, orbit] = file.shortcuts() else {
+            panic!("expected two shortcuts");
+        };
+        assert_eq!(quest.app_id(), 2502017475);
+        assert_eq!(quest.tags(), ["Favorite", "RPG"]);
+        assert_eq!(orbit.app_name(), "Orbit Runner");
+        assert_eq!(orbit.exe(), "\"/usr/bin/orbit-runner\"");
+        assert!(orbit.tags().is_empty());
+    }
+
+    #[test]
+    fn add_skips_games_already_present() {
+        let mut file = ShortcutsFile::parse(&steam_file()).unwrap();
+        let existing = Shortcut::new(
+            "Fixture Quest",
+            "C:\\Program Files\\Fixture Quest\\FixtureQuest.exe",
+            "C:\\Program Files\\Fixture Quest\\",
+            "-windowed",
+        );
+        assert_eq!(file.add(existing), (2502017475, false));
+        assert_eq!(file.serialize(), steam_file());
+
+        let new = Shortcut::new("Fixture Tactics", "C:\\Games\\FixtureTactics.exe", "C:\\Games", "");
+        let app_id = new.app_id();
+        assert_eq!(file.add(new), (app_id, true));
+
+        let reread = ShortcutsFile::parse(&file.serialize()).unwrap();
+        let names: Vec<&str> = reread.shortcuts().iter().map(Shortcut::app_name).collect();
+        assert_eq!(names, ["Fixture Quest", "Orbit Runner", "Fixture Tactics"]);
+        assert_eq!(reread.shortcuts()[1], file.shortcuts()[1]);
+    }
+
+    #[test]
+    fn entries_with_non_utf8_names_survive_an_import() {
+        let data = Builder::default()
+            .map("shortcuts")
+            .map("0")
+            .int32("appid", -1234567890)
+            .raw_string("AppName", b"\xc7a Marche")
+            .string("Exe", "\"C:\\Games\\CaMarche.exe\"")
+            .end()
+            .end()
+            .end()
+            .build();
+        let mut file = ShortcutsFile::parse(&data).unwrap();
+        assert_eq!(file.serialize(), data);
+
+        file.add(Shortcut::new("Fixture Tactics", "C:\\Games\\FixtureTactics.exe", "C:\\Games", ""));
+        let reread = ShortcutsFile::parse(&file.serialize()).unwrap();
+        assert_eq!(reread.shortcuts().len(), 2);
+        assert_eq!(reread.shortcuts()[0], file.shortcuts()[0]);
+        assert_eq!(
+            reread.shortcuts()[0].as_map().get("AppName"),
+            Some(&Value::RawString(b"\xc7a Marche".to_vec()))
+        );
+    }
+}
@@ -0,0 +1,102 @@
+// Steam accounts on this machine: folders under userdata/ joined with the
+// persona names and login times from config/loginusers.vdf.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::vdf::{Document, Object};
+
+/// Offset between a SteamID64 (loginusers.vdf keys) and the 32-bit account id
+/// used for userdata folder names.
+const STEAM_ID64_BASE: u64 = 76_561_197_960_265_728;
+
+/// Import target that selects every user on the machine.
+pub const ALL_USERS: &str = "all";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SteamUser {
+    /// 32-bit account id, the folder name under userdata/.
+    pub id: String,
+    pub steam_id64: Option<String>,
+    pub account_name: Option<String>,
+    pub persona_name: Option<String>,
+    pub most_recent: bool,
+    /// Unix time of the last login, if Steam recorded one.
+    pub last_login: Option<u64>,
+}
+
+pub fn account_id_from_steam_id64(steam_id64: u64) -> Option<u32> {
+    steam_id64
+        .checked_sub(STEAM_ID64_BASE)
+        .and_then(|id| u32::try_from(id).ok())
+}
+
+pub fn steam_id64_from_account_id(account_id: u32) -> u64 {
+    STEAM_ID64_BASE + u64::from(account_id)
+}
+
+/// All users with a userdata folder, most recently logged in first.
+pub fn list_users(steam_path: &Path) -> Vec<SteamUser> {
+    let login_users = Document::load(&steam_path.join("config").join("loginusers.vdf")).ok();
+    let login_users = login_users.as_ref().and_then(|doc| doc.root().get_object("users"));
+
+    let mut users: Vec<SteamUser> = super::userdata_ids(steam_path)
+        .into_iter()
+        .map(|id| {
+            let login = login_users.and_then(|users| find_login(users, &id));
+            match login {
+                Some((steam_id64, entry)) => SteamUser {
+                    steam_id64: Some(steam_id64.to_string()),
+                    account_name: entry.get_str("AccountName").map(str::to_string),
+                    persona_name: entry.get_str("PersonaName").map(str::to_string),
+                    most_recent: entry.get_str("MostRecent") == Some("1"),
+                    last_login: entry.get_str("Timestamp").and_then(|t| t.parse().ok()),
+                    id,
+                },
+                None => SteamUser {
+                    steam_id64: id.parse().ok().map(|id| steam_id64_from_account_id(id).to_string()),
+                    account_name: None,
+                    persona_name: None,
+                    most_recent: false,
+                    last_login: None,
+                    id,
+                },
+            }
+        })
+        .collect();
+
+    users.sort_by(|a, b| {
+        b.most_recent
+            .cmp(&a.most_recent)
+            .then(b.last_login.cmp(&a.last_login))
+            .then(a.id.cmp(&b.id))
+    });
+    users
+}
+
+fn find_login<'a>(users: &'a Object, account_id: &str) -> Option<(u64, &'a Object)> {
+    users.iter().find_map(|(key, value)| {
+        let steam_id64: u64 = key.parse().ok()?;
+        let matches = account_id_from_steam_id64(steam_id64)?.to_string() == account_id;
+        matches.then_some((steam_id64, value.as_object()?))
+    })
+}
+
+/// Resolves the import target: `"all"` selects every user, anything else must
+/// be the account id of an existing userdata folder.
+pub fn resolve_target(steam_path: &Path, user_id: &str) -> Result<Vec<String>, String> {
+    let ids = super::userdata_ids(steam_path);
+    if ids.is_empty() {
+        return Err("No Steam users found. Log in to Steam at least once before importing.".to_string());
+    }
+
+    if user_id.eq_ignore_ascii_case(ALL_USERS) {
+        return Ok(ids);
+    }
+    if ids.iter().any(|id| id == user_id) {
+        Ok(vec![user_id.to_string()])
+    } else {
+        Err(format!("Steam user {} was not found in {}", user_id, steam_path.join("userdata").display()))
+    }
+}
@@ -0,0 +1,733 @@
+// Text KeyValues ("VDF") as used by libraryfolders.vdf, loginusers.vdf,
+// config.vdf, localconfig.vdf and appmanifest_*.acf.
+//
+// The parser keeps the whitespace and comments around every token, the raw
+// spelling of unchanged tokens and any `[$CONDITION]` suffixes, so a document
+// that is parsed and written back is identical to the input. Only entries that
+// were added or modified are rendered in Steam's own tab-indented style,
+// with the file's own line endings. A UTF-8 byte order mark is kept too.
+//
+// Duplicate keys are allowed; lookups return the first match and compare keys
+// case-insensitively, as Steam does.
+
+use std::fmt::{self, Write};
+use std::fs;
+use std::path::Path;
+
+use super::with_suffix;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(String),
+    Object(Object),
+}
+
+impl Value {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            Value::Object(_) => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&Object> {
+        match self {
+            Value::Object(o) => Some(o),
+            Value::String(_) => None,
+        }
+    }
+
+    pub fn as_object_mut(&mut self) -> Option<&mut Object> {
+        match self {
+            Value::Object(o) => Some(o),
+            Value::String(_) => None,
+        }
+    }
+}
+
+/// A `[$WIN32]` / `[!$X360]` style platform conditional attached to an entry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    expr: String,
+    before: String,
+    after_value: bool,
+}
+
+impl Condition {
+    /// The expression between the brackets, e.g. `$WIN32` or `!$OSX`.
+    pub fn expr(&self) -> &str {
+        &self.expr
+    }
+
+    /// Evaluates the condition against the set of defined platform symbols
+    /// (`$WIN32`, `$LINUX`, ...). Supports `!`, `&&` and `||`.
+    pub fn evaluate(&self, defines: &[&str]) -> bool {
+        self.expr.split("||").any(|alternative| {
+            alternative.split("&&").all(|term| {
+                let term = term.trim();
+                match term.strip_prefix('!') {
+                    Some(symbol) => !defines.iter().any(|d| d.eq_ignore_ascii_case(symbol.trim())),
+                    None => defines.iter().any(|d| d.eq_ignore_ascii_case(term)),
+                }
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Token {
+    text: String,
+    raw: Option<String>,
+}
+
+impl Token {
+    fn new(text: &str) -> Self {
+        Self { text: text.to_string(), raw: None }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    leading: Option<String>,
+    key: Token,
+    separator: Option<String>,
+    value: Value,
+    value_raw: Option<String>,
+    condition: Option<Condition>,
+}
+
+impl Entry {
+    pub fn new(key: &str, value: Value) -> Self {
+        Self {
+            leading: None,
+            key: Token::new(key),
+            separator: None,
+            value,
+            value_raw: None,
+            condition: None,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key.text
+    }
+
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    pub fn value_mut(&mut self) -> &mut Value {
+        // The caller may change a string value, so its original spelling can
+        // no longer be trusted.
+        self.value_raw = None;
+        &mut self.value
+    }
+
+    pub fn condition(&self) -> Option<&Condition> {
+        self.condition.as_ref()
+    }
+
+    /// True if the entry has no conditional or its conditional holds.
+    pub fn applies(&self, defines: &[&str]) -> bool {
+        self.condition.as_ref().map_or(true, |c| c.evaluate(defines))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Object {
+    entries: Vec<Entry>,
+    close: Option<String>,
+}
+
+impl Object {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.entries.iter().map(|e| (e.key(), &e.value))
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.entries
+            .iter()
+            .find(|e| e.key.text.eq_ignore_ascii_case(key))
+            .map(|e| &e.value)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        self.entries
+            .iter_mut()
+            .find(|e| e.key.text.eq_ignore_ascii_case(key))
+            .map(|e| e.value_mut())
+    }
+
+    /// Every value stored under `key`, for files that repeat keys.
+    pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a Value> + 'a {
+        self.entries
+            .iter()
+            .filter(move |e| e.key.text.eq_ignore_ascii_case(key))
+            .map(|e| &e.value)
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(Value::as_str)
+    }
+
+    pub fn get_object(&self, key: &str) -> Option<&Object> {
+        self.get(key).and_then(Value::as_object)
+    }
+
+    pub fn get_object_mut(&mut self, key: &str) -> Option<&mut Object> {
+        self.get_mut(key).and_then(Value::as_object_mut)
+    }
+
+    /// Follows a chain of nested objects, e.g. `["InstallConfigStore", "Software", "Valve"]`.
+    pub fn get_path(&self, path: &[&str]) -> Option<&Value> {
+        let (last, parents) = path.split_last()?;
+        let mut object = self;
+        for key in parents {
+            object = object.get_object(key)?;
+        }
+        object.get(last)
+    }
+
+    /// Like `get_path`, but creates missing objects along the way.
+    pub fn object_at_path_mut(&mut self, path: &[&str]) -> &mut Object {
+        let mut object = self;
+        for key in path {
+            object = object.object_mut_or_insert(key);
+        }
+        object
+    }
+
+    /// Returns the child object under `key`, appending an empty one if the key
+    /// is missing. A string value under the same key is replaced.
+    pub fn object_mut_or_insert(&mut self, key: &str) -> &mut Object {
+        let index = match self.position(key) {
+            Some(index) => {
+                if !matches!(self.entries[index].value, Value::Object(_)) {
+                    self.entries[index].value = Value::Object(Object::new());
+                    self.entries[index].value_raw = None;
+                    self.entries[index].separator = None;
+                }
+                index
+            }
+            None => {
+                self.entries.push(Entry::new(key, Value::Object(Object::new())));
+                self.entries.len() - 1
+            }
+        };
+        match &mut self.entries[index].value {
+            Value::Object(o) => o,
+            Value::String(_) => unreachable!(),
+        }
+    }
+
+    /// Sets the first entry named `key`, keeping its position and surrounding
+    /// formatting, or appends a new entry.
+    pub fn set(&mut self, key: &str, value: Value) {
+        match self.position(key) {
+            Some(index) => {
+                let entry = &mut self.entries[index];
+                if entry.value != value {
+                    if std::mem::discriminant(&entry.value) != std::mem::discriminant(&value) {
+                        entry.separator = None;
+                    }
+                    entry.value = value;
+                    entry.value_raw = None;
+                }
+            }
+            None => self.entries.push(Entry::new(key, value)),
+        }
+    }
+
+    pub fn set_str(&mut self, key: &str, value: &str) {
+        self.set(key, Value::String(value.to_string()));
+    }
+
+    /// Appends an entry even if the key already exists.
+    pub fn push(&mut self, key: &str, value: Value) {
+        self.entries.push(Entry::new(key, value));
+    }
+
+    /// Removes every entry named `key` and returns the first removed value.
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        let mut removed = None;
+        let mut index = 0;
+        while index < self.entries.len() {
+            if self.entries[index].key.text.eq_ignore_ascii_case(key) {
+                let entry = self.entries.remove(index);
+                removed.get_or_insert(entry.value);
+            } else {
+                index += 1;
+            }
+        }
+        removed
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn position(&self, key: &str) -> Option<usize> {
+        self.entries.iter().position(|e| e.key.text.eq_ignore_ascii_case(key))
+    }
+}
+
+/// A whole file. The root is an object without braces.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Document {
+    root: Object,
+    bom: bool,
+    crlf: bool,
+}
+
+impl Document {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let bom = input.starts_with('\u{feff}');
+        let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+        // New entries follow the first line break in the file.
+        let crlf = input.find('\n').is_some_and(|end| input[..end].ends_with('\r'));
+        let mut parser = Parser { input, pos: 0 };
+        let root = parser.parse_object(true)?;
+        Ok(Self { root, bom, crlf })
+    }
+
+    /// Reads a file. Anything that is not UTF-8 is rejected rather than
+    /// replaced, since `save` would otherwise write the damage back.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let data = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let text = String::from_utf8(data).map_err(|e| format!("{} is not valid UTF-8: {}", path.display(), e))?;
+        Self::parse(&text).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+    }
+
+    /// Writes the document next to a `.bak` copy of the previous version,
+    /// through a temporary file so a crash never leaves a truncated config
+    /// behind.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if path.exists() {
+            fs::copy(path, with_suffix(path, ".bak"))
+                .map_err(|e| format!("Failed to back up {}: {}", path.display(), e))?;
+        }
+
+        let tmp = with_suffix(path, ".tmp");
+        fs::write(&tmp, self.to_string()).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+        fs::rename(&tmp, path).map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+    }
+
+    pub fn root(&self) -> &Object {
+        &self.root
+    }
+
+    pub fn root_mut(&mut self) -> &mut Object {
+        &mut self.root
+    }
+}
+
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let newline = if self.crlf { "\r\n" } else { "\n" };
+        let mut out = String::new();
+        if self.bom {
+            out.push('\u{feff}');
+        }
+        let start = out.len();
+        write_entries(&mut out, &self.root, 0, start, newline);
+        out.push_str(self.root.close.as_deref().unwrap_or(newline));
+        f.write_str(&out)
+    }
+}
+
+fn indent(depth: usize) -> String {
+    "\t".repeat(depth)
+}
+
+/// `start` is where the document begins in `out`, after any byte order mark.
+fn write_entries(out: &mut String, object: &Object, depth: usize, start: usize, newline: &str) {
+    for (index, entry) in object.entries.iter().enumerate() {
+        match &entry.leading {
+            Some(leading) => out.push_str(leading),
+            None if depth == 0 && index == 0 && out.len() == start => {}
+            None => {
+                out.push_str(newline);
+                out.push_str(&indent(depth));
+            }
+        }
+
+        out.push_str(entry.key.raw.as_deref().unwrap_or(&quote(&entry.key.text)));
+
+        if let Some(condition) = entry.condition.as_ref().filter(|c| !c.after_value) {
+            let _ = write!(out, "{}[{}]", condition.before, condition.expr);
+        }
+
+        match &entry.value {
+            Value::String(s) => {
+                out.push_str(entry.separator.as_deref().unwrap_or("\t\t"));
+                out.push_str(entry.value_raw.as_deref().unwrap_or(&quote(s)));
+            }
+            Value::Object(child) => {
+                match &entry.separator {
+                    Some(separator) => out.push_str(separator),
+                    None => {
+                        out.push_str(newline);
+                        out.push_str(&indent(depth));
+                    }
+                }
+                out.push('{');
+                write_entries(out, child, depth + 1, start, newline);
+                match &child.close {
+                    Some(close) => out.push_str(close),
+                    None => {
+                        out.push_str(newline);
+                        out.push_str(&indent(depth));
+                    }
+                }
+                out.push('}');
+            }
+        }
+
+        if let Some(condition) = entry.condition.as_ref().filter(|c| c.after_value) {
+            let _ = write!(out, "{}[{}]", condition.before, condition.expr);
+        }
+    }
+}
+
+fn quote(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn parse_object(&mut self, is_root: bool) -> Result<Object, String> {
+        let mut object = Object::new();
+        loop {
+            let trivia = self.read_trivia()?;
+            match self.peek() {
+                None if is_root => {
+                    object.close = Some(trivia);
+                    return Ok(object);
+                }
+                None => return Err("Unexpected end of file, missing '}'".to_string()),
+                Some('}') if !is_root => {
+                    self.pos += 1;
+                    object.close = Some(trivia);
+                    return Ok(object);
+                }
+                Some('}') | Some('{') => {
+                    return Err(format!("Unexpected '{}' at {}", self.peek().unwrap(), self.location()))
+                }
+                Some(_) => {}
+            }
+
+            let key = self.read_token()?;
+            let mut separator = self.read_trivia()?;
+
+            let mut condition = None;
+            if self.peek() == Some('[') {
+                let expr = self.read_condition()?;
+                condition = Some(Condition { expr, before: separator, after_value: false });
+                separator = self.read_trivia()?;
+            }
+
+            let (value, value_raw) = match self.peek() {
+                Some('{') => {
+                    self.pos += 1;
+                    (Value::Object(self.parse_object(false)?), None)
+                }
+                Some('}') | None => return Err(format!("Missing value for key '{}' at {}", key.text, self.location())),
+                Some(_) => {
+                    let token = self.read_token()?;
+                    (Value::String(token.text), token.raw)
+                }
+            };
+
+            if condition.is_none() {
+                let checkpoint = self.pos;
+                let before = self.read_trivia()?;
+                if self.peek() == Some('[') {
+                    let expr = self.read_condition()?;
+                    condition = Some(Condition { expr, before, after_value: true });
+                } else {
+                    self.pos = checkpoint;
+                }
+            }
+
+            object.entries.push(Entry {
+                leading: Some(trivia),
+                key,
+                separator: Some(separator),
+                value,
+                value_raw,
+                condition,
+            });
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn location(&self) -> String {
+        let line = self.input[..self.pos].matches('\n').count() + 1;
+        format!("line {}", line)
+    }
+
+    /// Consumes whitespace and `//` comments and returns them verbatim.
+    fn read_trivia(&mut self) -> Result<String, String> {
+        let start = self.pos;
+        loop {
+            let rest = &self.input[self.pos..];
+            if rest.starts_with("//") {
+                self.pos += rest.find('\n').unwrap_or(rest.len());
+            } else if let Some(c) = rest.chars().next().filter(|c| c.is_whitespace()) {
+                self.pos += c.len_utf8();
+            } else {
+                return Ok(self.input[start..self.pos].to_string());
+            }
+        }
+    }
+
+    fn read_token(&mut self) -> Result<Token, String> {
+        let start = self.pos;
+        if self.peek() == Some('"') {
+            self.pos += 1;
+            let mut text = String::new();
+            let mut chars = self.input[self.pos..].char_indices();
+            while let Some((offset, c)) = chars.next() {
+                match c {
+                    '"' => {
+                        self.pos += offset + 1;
+                        let raw = self.input[start..self.pos].to_string();
+                        return Ok(Token { text, raw: Some(raw) });
+                    }
+                    '\\' => match chars.next() {
+                        Some((_, 'n')) => text.push('\n'),
+                        Some((_, 't')) => text.push('\t'),
+                        Some((_, '\\')) => text.push('\\'),
+                        Some((_, '"')) => text.push('"'),
+                        Some((_, other)) => {
+                            text.push('\\');
+                            text.push(other);
+                        }
+                        None => break,
+                    },
+                    c => text.push(c),
+                }
+            }
+            self.pos = start;
+            return Err(format!("Unterminated string at {}", self.location()));
+        }
+
+        let rest = &self.input[self.pos..];
+        let len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '"' | '{' | '}' | '['))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(format!("Expected a key or value at {}", self.location()));
+        }
+        let text = rest[..len].to_string();
+        self.pos += len;
+        Ok(Token { raw: Some(text.clone()), text })
+    }
+
+    fn read_condition(&mut self) -> Result<String, String> {
+        let rest = &self.input[self.pos..];
+        let end = rest
+            .find(']')
+            .ok_or_else(|| format!("Unterminated conditional at {}", self.location()))?;
+        let expr = rest[1..end].to_string();
+        self.pos += end + 1;
+        Ok(expr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    const CONFIG: &str = "// Written by Steam\n\
+\"InstallConfigStore\"\n\
+{\n\
+\t// Software settings\n\
+\t\"Software\"\n\
+\t{\n\
+\t\t\"Valve\"\n\
+\t\t{\n\
+\t\t\t\"Steam\"\n\
+\t\t\t{\n\
+\t\t\t\t\"AutoUpdateWindowEnabled\"\t\t\"0\"   // trailing comment\n\
+\t\t\t\t\"Platform\"\t\t\"windows\"\t[$WIN32]\n\
+\t\t\t\t\"Platform\"\t\t\"linux\"\t[$LINUX]\n\
+\t\t\t\t\"Shell\" [!$WIN32]\t\t\"bash\"\n\
+\t\t\t\t\"BaseFolder\"\t\t\"C:\\\\Program Files (x86)\\\\Steam\"\n\
+\t\t\t\t\"Motd\"\t\t\"say \\\"hi\\\"\\tthere \\q\"\n\
+\t\t\t}\n\
+\t\t}\n\
+\t}\n\
+}\n";
+
+    const STEAM: [&str; 4] = ["InstallConfigStore", "Software", "Valve", "Steam"];
+
+    fn edit(input: &str, f: impl FnOnce(&mut Object)) -> String {
+        let mut doc = Document::parse(input).unwrap();
+        f(doc.root_mut().object_at_path_mut(&STEAM));
+        doc.to_string()
+    }
+
+    #[test]
+    fn unchanged_document_round_trips() {
+        assert_eq!(Document::parse(CONFIG).unwrap().to_string(), CONFIG);
+    }
+
+    #[test]
+    fn edits_keep_comments() {
+        let output = edit(CONFIG, |steam| steam.set_str("AutoUpdateWindowEnabled", "1"));
+        assert_eq!(output, CONFIG.replace("\"0\"", "\"1\""));
+        assert!(output.contains("\"1\"   // trailing comment\n"));
+        assert!(output.starts_with("// Written by Steam\n"));
+    }
+
+    #[test]
+    fn edits_keep_conditionals() {
+        let output = edit(CONFIG, |steam| steam.set_str("BaseFolder", "D:\\Steam"));
+        assert_eq!(output, CONFIG.replace("C:\\\\Program Files (x86)\\\\Steam", "D:\\\\Steam"));
+
+        let doc = Document::parse(&output).unwrap();
+        let steam = doc.root().get_path(&STEAM).and_then(Value::as_object).unwrap();
+        let platforms: Vec<(&str, bool)> = steam
+            .entries()
+            .iter()
+            .filter(|e| e.key() == "Platform")
+            .map(|e| (e.condition().unwrap().expr(), e.applies(&["$LINUX"])))
+            .collect();
+        assert_eq!(platforms, [("$WIN32", false), ("$LINUX", true)]);
+        let shell = steam.entries().iter().find(|e| e.key() == "Shell").unwrap();
+        assert_eq!(shell.condition().map(Condition::expr), Some("!$WIN32"));
+        assert!(shell.applies(&["$LINUX"]));
+    }
+
+    #[test]
+    fn duplicate_keys_survive_and_only_the_first_is_edited() {
+        let output = edit(CONFIG, |steam| {
+            assert_eq!(steam.get_all("platform").count(), 2);
+            steam.set_str("Platform", "win64");
+        });
+        assert_eq!(output, CONFIG.replace("\"windows\"", "\"win64\""));
+
+        let output = edit(CONFIG, |steam| {
+            let removed = steam.remove("Platform");
+            assert_eq!(removed.as_ref().and_then(Value::as_str), Some("windows"));
+        });
+        assert!(!output.contains("Platform"));
+    }
+
+    #[test]
+    fn escapes_are_decoded_and_kept() {
+        let doc = Document::parse(CONFIG).unwrap();
+        let steam = doc.root().get_path(&STEAM).and_then(Value::as_object).unwrap();
+        assert_eq!(steam.get_str("BaseFolder"), Some("C:\\Program Files (x86)\\Steam"));
+        assert_eq!(steam.get_str("motd"), Some("say \"hi\"\tthere \\q"));
+
+        // Untouched values keep their spelling, edited ones are escaped
+        let output = edit(CONFIG, |steam| steam.set_str("AutoUpdateWindowEnabled", "a \"b\"\\c"));
+        assert!(output.contains("\"Motd\"\t\t\"say \\\"hi\\\"\\tthere \\q\"\n"));
+        assert!(output.contains("\"AutoUpdateWindowEnabled\"\t\t\"a \\\"b\\\"\\\\c\"   // trailing comment"));
+    }
+
+    #[test]
+    fn odd_whitespace_is_kept_and_new_entries_use_tabs() {
+        let input = "\"root\"\r\n{\r\n    \"a\"   \"1\"\r\n  \"b\" \"2\"\r\n}";
+        let mut doc = Document::parse(input).unwrap();
+        let root = doc.root_mut().object_mut_or_insert("root");
+        root.set_str("b", "3");
+        root.set_str("c", "4");
+        root.object_mut_or_insert("d").set_str("e", "5");
+        assert_eq!(
+            doc.to_string(),
+            "\"root\"\r\n{\r\n    \"a\"   \"1\"\r\n  \"b\" \"3\"\r\n\t\"c\"\t\t\"4\"\r\n\t\"d\"\r\n\t{\r\n\t\t\"e\"\t\t\"5\"\r\n\t}\r\n}"
+        );
+    }
+
+    #[test]
+    fn byte_order_mark_is_kept() {
+        let input = "\u{feff}\"root\"\n{\n\t\"a\"\t\t\"1\"\n}\n";
+        let doc = Document::parse(input).unwrap();
+        assert_eq!(doc.root().get_object("root").and_then(|root| root.get_str("a")), Some("1"));
+        assert_eq!(doc.to_string(), input);
+
+        let mut doc = Document::new();
+        doc.root_mut().set_str("a", "1");
+        assert_eq!(doc.to_string(), "\"a\"\t\t\"1\"\n");
+    }
+
+    #[test]
+    fn new_objects_are_written_in_steam_style() {
+        let output = edit(CONFIG, |steam| {
+            steam.object_mut_or_insert("CompatToolMapping").object_mut_or_insert("123").set_str("name", "proton_9");
+        });
+        assert!(output.contains(
+            "\"bash\"\n\t\t\t\t\"BaseFolder\"\t\t\"C:\\\\Program Files (x86)\\\\Steam\"\n\t\t\t\t\"Motd\"\t\t\"say \\\"hi\\\"\\tthere \\q\"\n\
+\t\t\t\t\"CompatToolMapping\"\n\t\t\t\t{\n\t\t\t\t\t\"123\"\n\t\t\t\t\t{\n\t\t\t\t\t\t\"name\"\t\t\"proton_9\"\n\t\t\t\t\t}\n\t\t\t\t}\n\t\t\t}"
+        ));
+    }
+
+    #[test]
+    fn save_writes_what_load_read() {
+        let dir = TempDir::new("vdf");
+        let path = dir.join("config.vdf");
+        fs::write(&path, CONFIG).unwrap();
+
+        let mut doc = Document::load(&path).unwrap();
+        doc.root_mut().object_at_path_mut(&STEAM).set_str("AutoUpdateWindowEnabled", "1");
+        doc.save(&path).unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), CONFIG.replace("\"0\"", "\"1\""));
+        assert_eq!(fs::read_to_string(dir.join("config.vdf.bak")).unwrap(), CONFIG);
+        assert!(!dir.join("config.vdf.tmp").exists());
+    }
+
+    #[test]
+    fn load_rejects_invalid_utf8() {
+        let dir = TempDir::new("vdf-latin1");
+        let path = dir.join("loginusers.vdf");
+        fs::write(&path, b"\"users\"\n{\n\t\"PersonaName\"\t\t\"Jos\xe9\"\n}\n").unwrap();
+        assert!(Document::load(&path).unwrap_err().contains("UTF-8"));
+    }
+
+    #[test]
+    fn rejects_broken_documents() {
+        assert!(Document::parse("\"a\"\n{\n\t\"b\"\t\"c\"\n").is_err());
+        assert!(Document::parse("\"a\"\t\"unterminated").is_err());
+        assert!(Document::parse("\"a\"\n}").is_err());
+    }
+}
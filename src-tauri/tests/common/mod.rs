@@ -0,0 +1,55 @@
+// Shared helpers for the integration tests: every test works on its own copy
+// of a fixture machine, so imports can write to it freely.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[path = "../../src/test_support.rs"]
+mod test_support;
+
+use test_support::TempDir;
+
+/// Copies `tests/fixtures/<name>` to a fresh temporary folder, removed again
+/// when the returned guard is dropped. `{root}` in `.vdf` files is replaced
+/// with the copy's path, for records that hold absolute Linux paths.
+pub fn fixture_root(name: &str) -> TempDir {
+    let source = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures").join(name);
+    let target = TempDir::new(name);
+    copy_dir(&source, &target, &target);
+    target
+}
+
+fn copy_dir(source: &Path, target: &Path, root: &Path) {
+    fs::create_dir_all(target).unwrap();
+    for entry in fs::read_dir(source).unwrap() {
+        let entry = entry.unwrap();
+        let path = entry.path();
+        let dest = target.join(entry.file_name());
+        let file_type = entry.file_type().unwrap();
+        if file_type.is_symlink() {
+            copy_symlink(&path, &dest, root);
+        } else if file_type.is_dir() {
+            copy_dir(&path, &dest, root);
+        } else if path.extension().is_some_and(|ext| ext == "vdf") {
+            let text = fs::read_to_string(&path).unwrap();
+            fs::write(&dest, text.replace("{root}", &root.to_string_lossy())).unwrap();
+        } else {
+            fs::copy(&path, &dest).unwrap();
+        }
+    }
+}
+
+#[cfg(unix)]
+fn copy_symlink(source: &Path, target: &Path, _root: &Path) {
+    std::os::unix::fs::symlink(fs::read_link(source).unwrap(), target).unwrap();
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(source: &Path, target: &Path, root: &Path) {
+    copy_dir(source, target, root);
+}
+
+/// Steam root of the fixture machine.
+pub fn steam_root(root: &Path) -> PathBuf {
+    root.join("drive_c").join("Program Files (x86)").join("Steam")
+}
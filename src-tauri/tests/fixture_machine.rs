@@ -0,0 +1,233 @@
+// Detection and import run end-to-end against tests/fixtures/machine, a
+// fixture root standing in for a Windows install with Steam, the Epic Games
+// Launcher, standalone GOG games, a Proton prefix and a custom launcher.
+
+mod common;
+
+use std::path::Path;
+
+use no_more_launchers::launchers::epic::InstallConsistency;
+use no_more_launchers::sandbox;
+use no_more_launchers::steam::install::{self, InstallKind};
+use no_more_launchers::steam::shortcuts::{shortcuts_path, ShortcutsFile};
+use no_more_launchers::steam::vdf::Document;
+use no_more_launchers::{detect_all, import_games, GameInfo, LauncherInfo};
+
+const USER_ID: &str = "22202";
+
+fn launcher<'a>(launchers: &'a [LauncherInfo], id: &str) -> &'a LauncherInfo {
+    launchers.iter().find(|l| l.id == id).unwrap_or_else(|| panic!("launcher {} missing", id))
+}
+
+fn game_names(launcher: &LauncherInfo) -> Vec<&str> {
+    let mut names: Vec<&str> = launcher.games.iter().map(|g| g.name.as_str()).collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn detects_launchers_in_fixture_root() {
+    let root = common::fixture_root("machine");
+    let launchers = sandbox::with_root(root.path(), detect_all).unwrap();
+
+    let detected: Vec<&str> = launchers.iter().filter(|l| l.detected).map(|l| l.id.as_str()).collect();
+    assert_eq!(detected, ["epic", "steam", "gog", "studio"]);
+
+    let epic = launcher(&launchers, "epic");
+    assert_eq!(game_names(epic), ["Fixture Quest"]);
+    assert_eq!(epic.games[0].install_consistency, Some(InstallConsistency::Consistent));
+    assert_eq!(
+        epic.games[0].launch_uri.as_deref(),
+        Some("com.epicgames.launcher://apps/fixturens%3Afixtureitem%3AFixtureQuest?action=launch&silent=true")
+    );
+    assert_eq!(epic.skipped.len(), 1);
+    assert_eq!(epic.skipped[0].reason, "DLC for FixtureQuest");
+
+    let gog = launcher(&launchers, "gog");
+    assert_eq!(game_names(gog), ["Fixture Tactics", "Prefix Raiders"]);
+    let tactics = gog.games.iter().find(|g| g.id == "1207658930").unwrap();
+    assert_eq!(tactics.launch_options.as_deref(), Some("-windowed"));
+    assert_eq!(tactics.executable, "C:\\GOG Games\\Fixture Tactics\\FixtureTactics.exe");
+    assert_eq!(tactics.install_path, "C:\\GOG Games\\Fixture Tactics");
+    assert_eq!(gog.skipped.len(), 1);
+    assert_eq!(gog.skipped[0].id, "1207658939");
+
+    let studio = launcher(&launchers, "studio");
+    assert_eq!(game_names(studio), ["Orbit Runner"]);
+    assert!(studio.games[0].executable.ends_with("OrbitRunner.exe"));
+    assert_eq!(studio.games[0].launch_options.as_deref(), Some("--game orbit-runner"));
+    assert_eq!(studio.skipped.len(), 1);
+
+    assert!(!launcher(&launchers, "ubisoft").detected);
+    assert!(!launcher(&launchers, "heroic").detected);
+}
+
+#[test]
+fn finds_games_in_proton_prefixes() {
+    let root = common::fixture_root("machine");
+    let launchers = sandbox::with_root(root.path(), detect_all).unwrap();
+
+    let raiders = launcher(&launchers, "gog").games.iter().find(|g| g.name == "Prefix Raiders").unwrap();
+    assert_eq!(raiders.compat_app_id, Some(3000000001));
+    let prefix = raiders.wine_prefix.as_deref().unwrap();
+    assert!(Path::new(prefix).starts_with(common::steam_root(&root)));
+    assert!(Path::new(&raiders.executable).is_absolute());
+    assert!(Path::new(&raiders.executable).starts_with(&raiders.install_path));
+    assert!(Path::new(&raiders.executable).is_file());
+}
+
+#[test]
+fn locates_steam_in_fixture_root() {
+    let root = common::fixture_root("machine");
+    let installations = sandbox::with_root(root.path(), install::installations).unwrap();
+
+    assert_eq!(installations.len(), 1);
+    assert_eq!(installations[0].kind, InstallKind::Registry);
+    assert_eq!(Path::new(&installations[0].root), common::steam_root(&root));
+    assert_eq!(installations[0].users.len(), 1);
+    assert_eq!(installations[0].users[0].id, USER_ID);
+    assert_eq!(installations[0].users[0].persona_name.as_deref(), Some("Fixture Player"));
+}
+
+#[test]
+fn imports_detected_games_into_steam() {
+    let root = common::fixture_root("machine");
+    let (launchers, result) = sandbox::with_root(root.path(), || {
+        let launchers = detect_all();
+        let games: Vec<GameInfo> = launchers.iter().flat_map(|l| l.games.clone()).collect();
+        let result = import_games(&games, USER_ID, None, Some("proton_experimental".to_string()));
+        (launchers, result)
+    })
+    .unwrap();
+    let result = result.unwrap();
+
+    let game_count = launchers.iter().map(|l| l.games.len()).sum::<usize>();
+    assert_eq!(result.success as usize, game_count);
+    assert!(result.failed.is_empty());
+    assert!(result.warnings.is_empty());
+
+    let steam = common::steam_root(&root);
+    let file = ShortcutsFile::load(&shortcuts_path(&steam, USER_ID)).unwrap();
+    let mut names: Vec<&str> = file.shortcuts().iter().map(|s| s.app_name()).collect();
+    names.sort();
+    assert_eq!(names, ["Fixture Quest", "Fixture Tactics", "Orbit Runner", "Prefix Raiders"]);
+
+    for imported in &result.imported {
+        let shortcut = file.shortcuts().iter().find(|s| s.app_name() == imported.name).unwrap();
+        assert_eq!(shortcut.app_id(), imported.shortcut.app_id);
+    }
+
+    let raiders = file.shortcuts().iter().find(|s| s.app_name() == "Prefix Raiders").unwrap();
+    assert!(raiders.launch_options().starts_with("STEAM_COMPAT_DATA_PATH="));
+
+    // Every shortcut that runs a Windows executable gets the compatibility tool
+    let config = Document::load(&steam.join("config").join("config.vdf")).unwrap();
+    let tactics = result.imported.iter().find(|g| g.name == "Fixture Tactics").unwrap();
+    let mapping = config
+        .root()
+        .get_path(&[
+            "InstallConfigStore",
+            "Software",
+            "Valve",
+            "Steam",
+            "CompatToolMapping",
+            &tactics.shortcut.app_id.to_string(),
+            "name",
+        ])
+        .and_then(|value| value.as_str());
+    assert_eq!(mapping, Some("proton_experimental"));
+}
+
+#[test]
+fn reimport_does_not_duplicate_shortcuts() {
+    let root = common::fixture_root("machine");
+    let count = sandbox::with_root(root.path(), || {
+        let games: Vec<GameInfo> = detect_all().into_iter().flat_map(|l| l.games).collect();
+        import_games(&games, "all", None, None).unwrap();
+        let second = import_games(&games, "all", None, None).unwrap();
+        assert_eq!(second.success as usize, games.len());
+        games.len()
+    })
+    .unwrap();
+
+    let file = ShortcutsFile::load(&shortcuts_path(&common::steam_root(&root), USER_ID)).unwrap();
+    assert_eq!(file.shortcuts().len(), count);
+}
+
+#[test]
+fn games_with_the_same_title_keep_their_own_shortcuts() {
+    let root = common::fixture_root("machine");
+    let game = |launcher_id: &str, exe: &str| GameInfo {
+        id: "twin".to_string(),
+        name: "Twin Title".to_string(),
+        executable: exe.to_string(),
+        install_path: "C:\\Games\\Twin".to_string(),
+        launcher_id: launcher_id.to_string(),
+        ..Default::default()
+    };
+    let games = [game("gog", "TwinGog.exe"), game("epic", "TwinEpic.exe")];
+    let result = sandbox::with_root(root.path(), || import_games(&games, USER_ID, None, None)).unwrap().unwrap();
+
+    assert_eq!(result.success, 2);
+    let file = ShortcutsFile::load(&shortcuts_path(&common::steam_root(&root), USER_ID)).unwrap();
+    for (game, imported) in games.iter().zip(&result.imported) {
+        assert_eq!(imported.launcher_id, game.launcher_id);
+        let shortcut = file.shortcuts().iter().find(|s| s.exe().contains(&game.executable)).unwrap();
+        assert_eq!(shortcut.app_id(), imported.shortcut.app_id);
+    }
+    assert_ne!(result.imported[0].shortcut.app_id, result.imported[1].shortcut.app_id);
+}
+
+#[test]
+fn one_broken_user_does_not_fail_the_others() {
+    let root = common::fixture_root("machine");
+    let steam = common::steam_root(&root);
+    let broken = steam.join("userdata").join("33303").join("config");
+    std::fs::create_dir_all(&broken).unwrap();
+    std::fs::write(broken.join("shortcuts.vdf"), b"not a shortcuts file").unwrap();
+
+    let (games, result) = sandbox::with_root(root.path(), || {
+        let games: Vec<GameInfo> = detect_all().into_iter().flat_map(|l| l.games).collect();
+        let result = import_games(&games, "all", None, None).unwrap();
+        (games, result)
+    })
+    .unwrap();
+
+    assert_eq!(result.success as usize, games.len());
+    assert!(result.imported.iter().all(|g| g.user_id == USER_ID));
+    assert_eq!(result.failed.len(), games.len());
+    assert!(result.failed.iter().all(|f| f.ends_with("(user 33303)")));
+    assert_eq!(result.warnings.len(), 1);
+    let file = ShortcutsFile::load(&shortcuts_path(&steam, USER_ID)).unwrap();
+    assert_eq!(file.shortcuts().len(), games.len());
+}
+
+#[test]
+fn lookups_outside_a_root_are_untouched() {
+    let root = common::fixture_root("machine");
+    sandbox::with_root(root.path(), || assert_eq!(sandbox::root().as_deref(), Some(root.path()))).unwrap();
+    assert_eq!(sandbox::root(), None);
+    assert!(sandbox::with_root(root.join("missing"), || ()).is_err());
+}
+
+#[test]
+fn compat_tool_is_left_alone_while_steam_runs() {
+    let root = common::fixture_root("machine");
+    std::fs::create_dir_all(root.join("home").join(".steam")).unwrap();
+    std::fs::write(root.join("home").join(".steam").join("steam.pid"), "4242\n").unwrap();
+    std::fs::create_dir_all(root.join("proc").join("4242")).unwrap();
+    std::fs::write(root.join("proc").join("4242").join("comm"), "steam\n").unwrap();
+
+    let (games, result) = sandbox::with_root(root.path(), || {
+        let games: Vec<GameInfo> = detect_all().into_iter().flat_map(|l| l.games).collect();
+        let result = import_games(&games, USER_ID, None, Some("proton_experimental".to_string())).unwrap();
+        (games, result)
+    })
+    .unwrap();
+
+    // The shortcuts are still written; only the tool choice is refused.
+    assert_eq!(result.success as usize, games.len());
+    assert_eq!(result.warnings.len(), 1);
+    assert!(result.warnings[0].contains("Steam is running"));
+    assert!(!common::steam_root(&root).join("config").join("config.vdf").exists());
+}
@@ -0,0 +1,46 @@
+// A Steam Deck style install, tests/fixtures/deck: the Steam root is reached
+// through the ~/.steam/steam symlink while libraryfolders.vdf lists the real
+// ~/.local/share/Steam as library "0".
+#![cfg(unix)]
+
+mod common;
+
+use no_more_launchers::sandbox;
+use no_more_launchers::steam::install::{self, InstallKind};
+use no_more_launchers::steam::shortcuts::{shortcuts_path, ShortcutsFile};
+use no_more_launchers::steam::{compatdata, library_folders};
+use no_more_launchers::{detect_all, import_games, GameInfo};
+
+#[test]
+fn symlinked_steam_root_is_one_library() {
+    let root = common::fixture_root("deck");
+    let installations = sandbox::with_root(root.path(), install::installations).unwrap();
+
+    assert_eq!(installations.len(), 1);
+    assert_eq!(installations[0].kind, InstallKind::Native);
+    let steam = std::path::PathBuf::from(&installations[0].root);
+    assert!(steam.ends_with(".steam/steam"));
+
+    assert_eq!(library_folders(&steam), [steam.clone()]);
+    assert_eq!(compatdata::list(&steam).len(), 1);
+}
+
+#[test]
+fn proton_prefix_games_are_found_once() {
+    let root = common::fixture_root("deck");
+    let (games, result) = sandbox::with_root(root.path(), || {
+        let games: Vec<GameInfo> = detect_all().into_iter().flat_map(|l| l.games).collect();
+        let result = import_games(&games, "all", None, None).unwrap();
+        (games, result)
+    })
+    .unwrap();
+
+    let names: Vec<&str> = games.iter().map(|g| g.name.as_str()).collect();
+    assert_eq!(names, ["Deck Raiders"]);
+    assert_eq!(games[0].compat_app_id, Some(3000000002));
+    assert_eq!(result.success, 1);
+
+    let steam = root.join("home").join(".steam").join("steam");
+    let file = ShortcutsFile::load(&shortcuts_path(&steam, "1001")).unwrap();
+    assert_eq!(file.shortcuts().len(), 1);
+}
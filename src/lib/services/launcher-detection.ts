@@ -78,7 +78,7 @@ export class LauncherDetectionService {
 		},
 		rockstar: {
 			name: 'Rockstar Games Launcher',
-			icon: '🌟',
+			icon: '🎸',
 			registryKey: 'HKEY_LOCAL_MACHINE\\SOFTWARE\\WOW6432Node\\Rockstar Games\\Launcher',
 			commonPaths: [
 				'C:\\Program Files\\Rockstar Games\\Launcher',
@@ -1,5 +1,12 @@
 import { invoke } from '@tauri-apps/api/tauri';
 
+export type InstallConsistency =
+	| 'consistent'
+	| 'location_mismatch'
+	| 'manifest_only'
+	| 'dat_only'
+	| 'install_dir_missing';
+
 export interface GameInfo {
 	id: string;
 	name: string;
@@ -7,6 +14,20 @@ export interface GameInfo {
 	install_path: string;
 	launcher_id: string;
 	icon?: string;
+	launch_uri?: string;
+	install_consistency?: InstallConsistency;
+	launch_options?: string;
+	working_dir?: string;
+	wine_prefix?: string;
+	compat_app_id?: number;
+}
+
+export interface SkippedGame {
+	id: string;
+	name: string;
+	launcher_id: string;
+	reason: string;
+	install_consistency?: InstallConsistency;
 }
 
 export interface LauncherInfo {
@@ -16,11 +37,63 @@ export interface LauncherInfo {
 	detected: boolean;
 	games: GameInfo[];
 	install_path?: string;
+	skipped: SkippedGame[];
+}
+
+export interface SteamUser {
+	id: string;
+	steam_id64?: string;
+	account_name?: string;
+	persona_name?: string;
+	most_recent: boolean;
+	last_login?: number;
+}
+
+export interface SteamInstallation {
+	root: string;
+	kind: 'registry' | 'windows' | 'native' | 'flatpak' | 'snap';
+	users: SteamUser[];
+}
+
+/** Passed as `userId` to import into every Steam account on the machine. */
+export const ALL_STEAM_USERS = 'all';
+
+export interface ShortcutIds {
+	/** Unsigned 32-bit shortcut appid. */
+	app_id: number;
+	/** The same appid as stored (signed) in shortcuts.vdf. */
+	shortcut_app_id: number;
+	/** Legacy 64-bit game id, as a string since it does not fit in a number. */
+	game_id: string;
+	run_url: string;
+	grid_prefix: string;
+}
+
+export interface ImportedGame {
+	game_id: string;
+	name: string;
+	launcher_id: string;
+	user_id: string;
+	shortcut: ShortcutIds;
 }
 
 export interface ImportResult {
 	success: number;
 	failed: string[];
+	imported: ImportedGame[];
+	warnings: string[];
+}
+
+export interface CompatTool {
+	name: string;
+	display_name: string;
+	path: string;
+	source: 'custom' | 'proton';
+}
+
+export interface ScanResult {
+	games: GameInfo[];
+	skipped: SkippedGame[];
 }
 
 export class TauriLauncherDetectionService {
@@ -49,14 +122,45 @@ export class TauriLauncherDetectionService {
 			throw new Error(`Failed to get games for ${launcherId}: ${error}`);
 		}
 	}
+
+	async listWinePrefixes(): Promise<string[]> {
+		try {
+			const prefixes = await invoke<string[]>('list_wine_prefixes');
+			console.log('Wine prefixes:', prefixes);
+			return prefixes;
+		} catch (error) {
+			console.error('Failed to list Wine prefixes:', error);
+			return [];
+		}
+	}
+
+	async scanWinePrefix(prefix: string): Promise<ScanResult> {
+		try {
+			console.log(`Scanning Wine prefix: ${prefix}`);
+			const result = await invoke<ScanResult>('scan_wine_prefix', { prefix });
+			console.log(`Found ${result.games.length} games in ${prefix}`);
+			return result;
+		} catch (error) {
+			console.error(`Failed to scan Wine prefix ${prefix}:`, error);
+			throw new Error(`Failed to scan Wine prefix ${prefix}: ${error}`);
+		}
+	}
 }
 
 export class TauriSteamIntegrationService {
-	async addGamesToSteam(games: GameInfo[]): Promise<ImportResult> {
+	async addGamesToSteam(
+		games: GameInfo[],
+		userId: string,
+		steamPath?: string,
+		compatTool?: string
+	): Promise<ImportResult> {
 		try {
-			console.log(`Adding ${games.length} games to Steam...`);
+			console.log(`Adding ${games.length} games to Steam for user ${userId}...`);
 			const result = await invoke<ImportResult>('add_games_to_steam', {
-				games
+				games,
+				userId,
+				steamPath,
+				compatTool
 			});
 			console.log('Import result:', result);
 			return result;
@@ -66,11 +170,44 @@ export class TauriSteamIntegrationService {
 		}
 	}
 
-	async addGameToSteam(game: GameInfo): Promise<boolean> {
-		const result = await this.addGamesToSteam([game]);
+	async addGameToSteam(game: GameInfo, userId: string, steamPath?: string): Promise<boolean> {
+		const result = await this.addGamesToSteam([game], userId, steamPath);
 		return result.success > 0;
 	}
 
+	async listSteamInstallations(): Promise<SteamInstallation[]> {
+		try {
+			const installations = await invoke<SteamInstallation[]>('list_steam_installations');
+			console.log('Steam installations:', installations);
+			return installations;
+		} catch (error) {
+			console.error('Failed to list Steam installations:', error);
+			return [];
+		}
+	}
+
+	async listCompatTools(steamPath?: string): Promise<CompatTool[]> {
+		try {
+			const tools = await invoke<CompatTool[]>('list_compat_tools', { steamPath });
+			console.log('Compatibility tools:', tools);
+			return tools;
+		} catch (error) {
+			console.error('Failed to list compatibility tools:', error);
+			return [];
+		}
+	}
+
+	async listSteamUsers(steamPath?: string): Promise<SteamUser[]> {
+		try {
+			const users = await invoke<SteamUser[]>('list_steam_users', { steamPath });
+			console.log('Steam users:', users);
+			return users;
+		} catch (error) {
+			console.error('Failed to list Steam users:', error);
+			return [];
+		}
+	}
+
 	async checkSteamPath(): Promise<string | null> {
 		try {
 			const steamPath = await invoke<string | null>('check_steam_path');